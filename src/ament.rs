@@ -0,0 +1,391 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::distro::{detect_variant, validate_distro, Distribution, SourceKind};
+use crate::quote::quote_posix;
+use crate::shell::pixi_global_env_name;
+use crate::sources::installed_distributions;
+
+const RESOURCE_INDEX: &str = "share/ament_index/resource_index";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AmentPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub build_type: Option<String>,
+}
+
+pub fn has_ament_index(prefix: &Path) -> bool {
+    prefix.join(RESOURCE_INDEX).join("packages").is_dir()
+}
+
+// Names registered under a resource type, e.g. `packages` or `rosidl_interfaces`
+pub fn resource_names(prefix: &Path, resource_type: &str) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(prefix.join(RESOURCE_INDEX).join(resource_type))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn strip_comments(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("<!--") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start..].find("-->") {
+            Some(end) => &rest[start + end + 3..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+// Text of the first `<tag>` element; the tag may carry attributes, as in
+// `<version compatibility="1.0.0">`
+fn xml_element(content: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut rest = content;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        if !rest.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            continue;
+        }
+        let body = &rest[rest.find('>')? + 1..];
+        let text = body[..body.find(&close)?].trim();
+        return (!text.is_empty()).then(|| text.to_string());
+    }
+    None
+}
+
+pub fn read_package(prefix: &Path, name: &str) -> AmentPackage {
+    let content = fs::read_to_string(prefix.join("share").join(name).join("package.xml"))
+        .map(|content| strip_comments(&content))
+        .unwrap_or_default();
+
+    AmentPackage {
+        name: name.to_string(),
+        version: xml_element(&content, "version"),
+        build_type: xml_element(&content, "build_type"),
+    }
+}
+
+pub fn read_ament_packages(prefix: &Path) -> Vec<AmentPackage> {
+    resource_names(prefix, "packages")
+        .iter()
+        .map(|name| read_package(prefix, name))
+        .collect()
+}
+
+pub fn cmd_pkgs(
+    distro: &str,
+    grep: Option<&str>,
+    build_type: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+    if !has_ament_index(&distro_path) {
+        anyhow::bail!(
+            "No ament index in {}\n\nPackage listing needs a ROS 2 installation",
+            distro_path.display()
+        );
+    }
+
+    let all = read_ament_packages(&distro_path);
+    let grep = grep.map(str::to_lowercase);
+    let packages: Vec<_> = all
+        .iter()
+        .filter(|p| {
+            grep.as_ref()
+                .is_none_or(|g| p.name.to_lowercase().contains(g))
+        })
+        .filter(|p| build_type.is_none_or(|b| p.build_type.as_deref() == Some(b)))
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&packages)?);
+        return Ok(());
+    }
+
+    let name_width = packages.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let version_width = packages
+        .iter()
+        .map(|p| p.version.as_deref().unwrap_or("-").len())
+        .max()
+        .unwrap_or(0);
+
+    for package in &packages {
+        let line = format!(
+            "{:<nw$}  {:<vw$}  {}",
+            package.name,
+            package.version.as_deref().unwrap_or("-"),
+            package.build_type.as_deref().unwrap_or(""),
+            nw = name_width,
+            vw = version_width,
+        );
+        println!("{}", line.trim_end());
+    }
+
+    println!("\n{} of {} packages", packages.len(), all.len());
+    Ok(())
+}
+
+// apt and RoboStack both name packages `ros-<distro>-<name>`, with dashes
+// for underscores
+pub fn ros_package_name(distro: &str, package: &str) -> String {
+    format!("ros-{}-{}", distro, package.replace('_', "-"))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PackageMatch {
+    pub distro: String,
+    pub variant: Option<String>,
+    pub version: Option<String>,
+}
+
+pub fn find_package(distros: &[Distribution], package: &str) -> Vec<PackageMatch> {
+    distros
+        .iter()
+        .filter(|d| {
+            resource_names(&d.path, "packages")
+                .iter()
+                .any(|n| n == package)
+        })
+        .map(|d| PackageMatch {
+            distro: d.name.clone(),
+            variant: detect_variant(&d.path, &d.name),
+            version: read_package(&d.path, package).version,
+        })
+        .collect()
+}
+
+// How to add a package to a distribution, for the kinds rosenv knows how to
+// install into; source builds and manual installs get no hint
+pub fn install_hint(distro: &Distribution, package: &str) -> Option<String> {
+    let name = ros_package_name(&distro.name, package);
+    match distro.kind {
+        SourceKind::PixiGlobal => {
+            let env = pixi_global_env_name(&distro.path)?;
+            Some(format!("pixi global add --environment {} {}", env, name))
+        }
+        SourceKind::Apt => Some(format!("sudo apt install {}", name)),
+        SourceKind::CondaEnv => {
+            let prefix = fs::canonicalize(&distro.path).unwrap_or_else(|_| distro.path.clone());
+            Some(format!(
+                "conda install --prefix {} -c robostack-staging {}",
+                quote_posix(&prefix.display().to_string()),
+                name
+            ))
+        }
+        SourceKind::SourceBuild | SourceKind::Manual => None,
+    }
+}
+
+pub fn cmd_find_pkg(package: &str) -> Result<()> {
+    let distros: Vec<_> = installed_distributions()?
+        .into_iter()
+        .filter(|d| has_ament_index(&d.path))
+        .collect();
+    if distros.is_empty() {
+        anyhow::bail!("No ROS 2 distributions with an ament index found\n\nRun: rosenv setup");
+    }
+
+    let matches = find_package(&distros, package);
+    if matches.is_empty() {
+        let hints: Vec<String> = distros
+            .iter()
+            .filter_map(|d| install_hint(d, package))
+            .map(|hint| format!("  {}", hint))
+            .collect();
+        if hints.is_empty() {
+            anyhow::bail!("Package '{}' not found in any distribution", package);
+        }
+        anyhow::bail!(
+            "Package '{}' not found in any distribution\n\nInstall it with:\n{}",
+            package,
+            hints.join("\n")
+        );
+    }
+
+    let name_width = matches.iter().map(|m| m.distro.len()).max().unwrap_or(0);
+    let variant_width = matches
+        .iter()
+        .map(|m| m.variant.as_deref().unwrap_or("-").len())
+        .max()
+        .unwrap_or(0);
+
+    println!("{} is provided by:", package);
+    for found in &matches {
+        println!(
+            "  {:<nw$}  {:<vw$}  {}",
+            found.distro,
+            found.variant.as_deref().unwrap_or("-"),
+            found.version.as_deref().unwrap_or("-"),
+            nw = name_width,
+            vw = variant_width,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_xml_element() {
+        let xml = r#"<?xml version="1.0"?>
+<package format="3">
+  <name>rclcpp</name>
+  <!-- <version>0.0.0</version> -->
+  <version compatibility="28.1.0">28.1.5</version>
+  <version_note>ignored</version_note>
+  <export>
+    <build_type>ament_cmake</build_type>
+  </export>
+</package>"#;
+        let xml = strip_comments(xml);
+
+        assert_eq!(xml_element(&xml, "version").as_deref(), Some("28.1.5"));
+        assert_eq!(
+            xml_element(&xml, "build_type").as_deref(),
+            Some("ament_cmake")
+        );
+        assert_eq!(xml_element(&xml, "license"), None);
+    }
+
+    #[test]
+    fn test_ros_package_name() {
+        assert_eq!(
+            ros_package_name("jazzy", "nav2_bringup"),
+            "ros-jazzy-nav2-bringup"
+        );
+    }
+
+    #[test]
+    fn test_find_package_and_install_hints() {
+        let root = std::env::temp_dir().join(format!("rosenv-find-{}", std::process::id()));
+        let distribution = |name: &str, kind| Distribution {
+            name: name.to_string(),
+            path: root.join(name),
+            kind,
+            declared: None,
+        };
+        let distros = [
+            distribution("humble", SourceKind::Apt),
+            distribution("jazzy", SourceKind::CondaEnv),
+            distribution("rolling", SourceKind::SourceBuild),
+        ];
+        for distro in &distros {
+            fs::create_dir_all(distro.path.join(RESOURCE_INDEX).join("packages")).unwrap();
+        }
+        fs::write(
+            root.join("jazzy")
+                .join(RESOURCE_INDEX)
+                .join("packages/nav2_bringup"),
+            "",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("jazzy/share/nav2_bringup")).unwrap();
+        fs::write(
+            root.join("jazzy/share/nav2_bringup/package.xml"),
+            "<package><version>1.3.4</version></package>",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("jazzy/conda-meta")).unwrap();
+        fs::write(
+            root.join("jazzy/conda-meta/ros-jazzy-desktop-0.11.0-np126py312_11.json"),
+            "{}",
+        )
+        .unwrap();
+
+        let found = find_package(&distros, "nav2_bringup");
+        let missing = find_package(&distros, "nav2_bringup_extra");
+        let hints: Vec<_> = distros
+            .iter()
+            .map(|d| install_hint(d, "nav2_bringup"))
+            .collect();
+        let jazzy = fs::canonicalize(root.join("jazzy")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            found,
+            vec![PackageMatch {
+                distro: "jazzy".to_string(),
+                variant: Some("desktop".to_string()),
+                version: Some("1.3.4".to_string()),
+            }]
+        );
+        assert!(missing.is_empty());
+        assert_eq!(
+            hints,
+            vec![
+                Some("sudo apt install ros-humble-nav2-bringup".to_string()),
+                Some(format!(
+                    "conda install --prefix '{}' -c robostack-staging ros-jazzy-nav2-bringup",
+                    jazzy.display()
+                )),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_hint_needs_known_pixi_env() {
+        let distro = Distribution {
+            name: "jazzy".to_string(),
+            path: PathBuf::from("/nonexistent/opt/ros/jazzy"),
+            kind: SourceKind::PixiGlobal,
+            declared: None,
+        };
+        assert_eq!(install_hint(&distro, "nav2_bringup"), None);
+    }
+
+    #[test]
+    fn test_read_ament_packages() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-ament-{}", std::process::id()));
+        let index = prefix.join(RESOURCE_INDEX).join("packages");
+        fs::create_dir_all(&index).unwrap();
+        fs::write(index.join("rclpy"), "").unwrap();
+        fs::write(index.join("ament_index_cpp"), "").unwrap();
+        fs::create_dir_all(prefix.join("share/rclpy")).unwrap();
+        fs::write(
+            prefix.join("share/rclpy/package.xml"),
+            "<package><version>7.1.1</version>\n<export><build_type>ament_python</build_type></export></package>",
+        )
+        .unwrap();
+
+        let found = has_ament_index(&prefix);
+        let packages = read_ament_packages(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert!(found);
+        assert_eq!(
+            packages,
+            vec![
+                AmentPackage {
+                    name: "ament_index_cpp".to_string(),
+                    version: None,
+                    build_type: None,
+                },
+                AmentPackage {
+                    name: "rclpy".to_string(),
+                    version: Some("7.1.1".to_string()),
+                    build_type: Some("ament_python".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+# rosenv direnv integration
+# Generated by: rosenv direnv-stdlib
+#
+# Install:  rosenv direnv-stdlib >> ~/.config/direnv/direnvrc
+# Use in .envrc:
+#   use ros jazzy
+#   use ros jazzy --overlay install
+
+use_ros() {
+    local distro="$1"
+    if [ -z "$distro" ]; then
+        log_error "use ros: missing distribution name"
+        return 1
+    fi
+    shift
+
+    local -a args=()
+    while [ $# -gt 0 ]; do
+        case "$1" in
+            --overlay)
+                if [ -z "$2" ]; then
+                    log_error "use ros: --overlay requires a path"
+                    return 1
+                fi
+                args+=(--overlay "$(expand_path "$2")")
+                watch_file "$2/.colcon_install_layout"
+                shift 2
+                ;;
+            *)
+                log_error "use ros: unknown argument '$1'"
+                return 1
+                ;;
+        esac
+    done
+
+    # Reload when packages are added to or removed from the distribution
+    watch_file "/opt/ros/$distro/conda-meta/history"
+
+    local env_script
+    env_script="$(rosenv env "$distro" --format shell "${args[@]}")" || return 1
+    eval "$env_script"
+}
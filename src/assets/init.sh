@@ -4,6 +4,13 @@
 rosenv() {
     case "$1" in
         activate)
+            case " $* " in
+                *" --explain "*)
+                    command rosenv "$@"
+                    return $?
+                    ;;
+            esac
+
             if [ -z "$2" ]; then
                 echo "Error: rosenv activate requires a distribution name"
                 echo "Available: $(command rosenv list --short 2>/dev/null || echo 'run rosenv setup')"
@@ -11,10 +18,10 @@ rosenv() {
             fi
             
             local script
-            script=$(command rosenv activate "$2" 2>&1)
+            script=$(command rosenv activate --shell {shell} "$2" 2>&1)
             if [ $? -eq 0 ]; then
                 eval "$script"
-                echo "✓ Switched to ROS 2 $2"
+                echo "✓ Switched to ROS ${ROS_VERSION:-2} $2"
             else
                 echo "$script" >&2
                 return 1
@@ -27,26 +34,17 @@ rosenv() {
                 return 1
             fi
             
-            local distro="$ROS_DISTRO"
-            eval "$(command rosenv deactivate)"
-            echo "✓ Deactivated ROS 2 $distro"
+            local distro="$ROS_DISTRO" version="${ROS_VERSION:-2}"
+            eval "$(command rosenv deactivate --shell {shell})"
+            echo "✓ Deactivated ROS $version $distro"
             ;;
             
         status)
-            if [ -n "$ROS_DISTRO" ]; then
-                echo "ROS 2 $ROS_DISTRO is active"
+            command rosenv status || return $?
+            if [ -n "$ROS_DISTRO" ] && command -v ros2 &>/dev/null; then
                 echo ""
-                [ -n "$ROS_VERSION" ] && echo "  ROS_VERSION:       $ROS_VERSION"
-                echo "  ROS_DISTRO:        $ROS_DISTRO"
-                [ -n "$AMENT_PREFIX_PATH" ] && echo "  AMENT_PREFIX_PATH: ${AMENT_PREFIX_PATH%%:*}..."
-                
-                if command -v ros2 &>/dev/null; then
-                    echo ""
-                    echo "ROS 2 CLI:"
-                    echo "  ✓ $(which ros2)"
-                fi
-            else
-                command rosenv status
+                echo "ROS 2 CLI:"
+                echo "  ✓ $(which ros2)"
             fi
             ;;
             
@@ -56,5 +54,57 @@ rosenv() {
     esac
 }
 
+# Activate the distribution pinned by .ros-distro/.rosenv.toml when entering a
+# directory and restore the previous one when leaving. Set ROSENV_AUTO=0 to disable.
+_rosenv_auto() {
+    [ "${ROSENV_AUTO:-1}" = "0" ] && return
+
+    local dir="$PWD" found=""
+    while :; do
+        if [ -f "$dir/.ros-distro" ]; then found="$dir/.ros-distro"; break; fi
+        if [ -f "$dir/.rosenv.toml" ]; then found="$dir/.rosenv.toml"; break; fi
+        [ -z "$dir" ] && break
+        dir="${dir%/*}"
+    done
+
+    [ "$found" = "${_ROSENV_AUTO_FILE:-}" ] && return
+
+    if [ -n "$found" ]; then
+        _ROSENV_AUTO_FILE="$found"
+        local distro prev="${ROS_DISTRO:-}"
+        if ! distro=$(command rosenv local 2>&1); then
+            echo "$distro" >&2
+            return
+        fi
+        [ "$distro" = "$prev" ] && return
+        rosenv activate "$distro" || return
+        # Only the first successful switch records what to go back to
+        if [ -z "${_ROSENV_AUTO_ACTIVE:-}" ]; then
+            _ROSENV_AUTO_PREV="$prev"
+            _ROSENV_AUTO_ACTIVE=1
+        fi
+    else
+        _ROSENV_AUTO_FILE=""
+        [ -z "${_ROSENV_AUTO_ACTIVE:-}" ] && return
+        if [ -n "${_ROSENV_AUTO_PREV:-}" ]; then
+            [ "$_ROSENV_AUTO_PREV" != "${ROS_DISTRO:-}" ] && rosenv activate "$_ROSENV_AUTO_PREV"
+        elif [ -n "${ROS_DISTRO:-}" ]; then
+            rosenv deactivate
+        fi
+        unset _ROSENV_AUTO_PREV _ROSENV_AUTO_ACTIVE
+    fi
+}
+
+if [ -n "$ZSH_VERSION" ]; then
+    autoload -Uz add-zsh-hook
+    add-zsh-hook chpwd _rosenv_auto
+elif [ -n "$BASH_VERSION" ]; then
+    case ";${PROMPT_COMMAND:-};" in
+        *";_rosenv_auto;"*) ;;
+        *) PROMPT_COMMAND="_rosenv_auto${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+    esac
+fi
+_rosenv_auto
+
 # Auto-activate default distribution on shell startup (optional)
 # rosenv activate humble >/dev/null 2>&1
@@ -0,0 +1,14 @@
+# ROS prompt segment, e.g. "(jazzy+1) "
+_rosenv_prompt() {
+    [ -n "$ROS_DISTRO" ] || return
+    local segment
+    segment=$(command rosenv prompt 2>/dev/null)
+    [ -n "$segment" ] && printf '(%s) ' "$segment"
+}
+
+if [ -n "$ZSH_VERSION" ]; then
+    setopt PROMPT_SUBST
+    PROMPT='$(_rosenv_prompt)'"$PROMPT"
+else
+    PS1='$(_rosenv_prompt)'"$PS1"
+fi
@@ -1,4 +1,7 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::export::ExportFormat;
 
 #[derive(Parser)]
 #[command(name = "rosenv")]
@@ -31,21 +34,145 @@ pub enum Commands {
     Activate {
         /// Distribution name (e.g., humble, jazzy)
         distro: String,
+
+        /// Show the environment changes instead of emitting shell code
+        #[arg(long)]
+        explain: bool,
+
+        /// Output the explanation as JSON
+        #[arg(long, requires = "explain")]
+        json: bool,
+
+        /// Shell to generate code for (defaults to the one in $SHELL)
+        #[arg(long, value_name = "SHELL")]
+        shell: Option<String>,
     },
 
     /// Generate shell commands to deactivate ROS environment
-    Deactivate,
+    Deactivate {
+        /// Shell to generate code for (defaults to the one in $SHELL)
+        #[arg(long, value_name = "SHELL")]
+        shell: Option<String>,
+    },
+
+    /// Print the environment of a distribution in a file format
+    Env {
+        /// Distribution name
+        distro: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "shell")]
+        format: ExportFormat,
+
+        /// Start from a minimal environment instead of the current one
+        #[arg(long)]
+        clean: bool,
+
+        /// colcon install directory to layer on top (repeatable)
+        #[arg(long, value_name = "PATH")]
+        overlay: Vec<PathBuf>,
+    },
+
+    /// Print the direnv `use ros` function for ~/.config/direnv/direnvrc
+    DirenvStdlib,
+
+    /// Run a command with a distribution's environment
+    Exec {
+        /// Distribution name
+        distro: String,
+
+        /// Start from a minimal environment instead of the current one
+        #[arg(long)]
+        clean: bool,
+
+        /// Command to run, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Start a subshell with a distribution active
+    Shell {
+        /// Distribution name
+        distro: String,
+    },
+
+    /// Print the distribution pinned for the current directory
+    Local {
+        /// Print the path of the .ros-distro/.rosenv.toml file instead
+        #[arg(long)]
+        path: bool,
+    },
 
     /// Show information about a distribution
     Info {
         /// Distribution name
         distro: String,
+
+        /// List installed packages from conda-meta
+        #[arg(long)]
+        packages: bool,
+
+        /// Only list ROS packages
+        #[arg(long, requires = "packages")]
+        ros_only: bool,
+
+        /// Only list packages whose name contains this text
+        #[arg(long, requires = "packages", value_name = "TEXT")]
+        grep: Option<String>,
+    },
+
+    /// List ROS packages from a distribution's ament index
+    Pkgs {
+        /// Distribution name
+        distro: String,
+
+        /// Only list packages whose name contains this text
+        #[arg(long, value_name = "TEXT")]
+        grep: Option<String>,
+
+        /// Only list packages with this build type (e.g. ament_python)
+        #[arg(long, value_name = "TYPE")]
+        build_type: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find which distributions provide a ROS package
+    FindPkg {
+        /// Package name (e.g., nav2_bringup)
+        package: String,
+    },
+
+    /// Compare the packages and executables of two distributions
+    Diff {
+        /// First distribution (e.g., humble)
+        a: String,
+
+        /// Second distribution (e.g., jazzy)
+        b: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Generate shell integration code
     Init {
         /// Shell type (zsh, bash)
         shell: String,
+
+        /// Also add a ROS segment to the shell prompt
+        #[arg(long)]
+        prompt: bool,
+    },
+
+    /// Print a compact prompt segment for the active distribution
+    Prompt {
+        /// Fields: {distro}, {variant}, {profile}, {overlay}, {flags}; `{field?}` only when set
+        #[arg(long, default_value = crate::prompt::DEFAULT_FORMAT)]
+        format: String,
     },
 
     /// Remove a distribution symlink
@@ -68,6 +195,12 @@ pub enum Commands {
         #[command(subcommand)]
         command: PixiCommands,
     },
+
+    /// Manage per-distribution colcon defaults
+    ColconDefaults {
+        #[command(subcommand)]
+        command: ColconDefaultsCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -75,3 +208,12 @@ pub enum PixiCommands {
     /// Generate activation script for pixi workspace
     Activate,
 }
+
+#[derive(Subcommand)]
+pub enum ColconDefaultsCommands {
+    /// Edit ~/.config/rosenv/colcon/<distro>/defaults.yaml in $EDITOR
+    Edit {
+        /// Distribution name
+        distro: String,
+    },
+}
@@ -0,0 +1,191 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{expand_home, load_config, ColconConfig};
+use crate::distro::validate_distro_name;
+use crate::env::EnvMap;
+
+pub const COLCON_VARS: [&str; 2] = ["COLCON_DEFAULTS_FILE", "COLCON_HOME"];
+
+const DEFAULTS_TEMPLATE: &str = "\
+# colcon defaults used while {distro} is active, see
+# https://colcon.readthedocs.io/en/released/user/configuration.html#defaults-yaml
+#
+# build:
+#   merge-install: true
+#   mixin: [release, compile-commands]
+#   cmake-args: [-DCMAKE_BUILD_TYPE=RelWithDebInfo]
+";
+
+// `dir` may come from config.toml as `~/...`
+fn colcon_dir(config: &ColconConfig) -> PathBuf {
+    expand_home(&config.dir)
+}
+
+pub fn distro_colcon_dir(config: &ColconConfig, distro: &str) -> PathBuf {
+    colcon_dir(config).join(distro)
+}
+
+pub fn defaults_file(config: &ColconConfig, distro: &str) -> PathBuf {
+    distro_colcon_dir(config, distro).join("defaults.yaml")
+}
+
+// Values the user set themselves are left alone; only ours point into `dir`
+fn is_ours(config: &ColconConfig, value: &str) -> bool {
+    Path::new(value).starts_with(colcon_dir(config))
+}
+
+pub fn clear_colcon(env: &mut EnvMap, config: &ColconConfig) {
+    for var in COLCON_VARS {
+        if env.get(var).is_some_and(|value| is_ours(config, value)) {
+            env.remove(var);
+        }
+    }
+}
+
+// Runs after clear_colcon, so anything still set is the user's own and wins;
+// deactivation then has nothing to restore
+pub fn apply_colcon(env: &mut EnvMap, config: &ColconConfig, distro: &str) {
+    let defaults = defaults_file(config, distro);
+    if defaults.is_file() && !env.contains_key("COLCON_DEFAULTS_FILE") {
+        env.insert(
+            "COLCON_DEFAULTS_FILE".to_string(),
+            defaults.display().to_string(),
+        );
+    }
+
+    if config.home && !env.contains_key("COLCON_HOME") {
+        env.insert(
+            "COLCON_HOME".to_string(),
+            distro_colcon_dir(config, distro).display().to_string(),
+        );
+    }
+}
+
+fn editor_command() -> Vec<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    editor.split_whitespace().map(String::from).collect()
+}
+
+pub fn cmd_edit(distro: &str) -> Result<()> {
+    validate_distro_name(distro)?;
+    let config = load_config()?;
+
+    let path = defaults_file(&config.colcon, distro);
+    if !path.exists() {
+        let dir = distro_colcon_dir(&config.colcon, distro);
+        fs::create_dir_all(&dir).context(format!("Failed to create {}", dir.display()))?;
+        fs::write(&path, DEFAULTS_TEMPLATE.replace("{distro}", distro))
+            .context(format!("Failed to write {}", path.display()))?;
+    }
+
+    let editor = editor_command();
+    let Some((program, args)) = editor.split_first() else {
+        bail!("$EDITOR is empty");
+    };
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(&path)
+        .status()
+        .context(format!("Failed to run editor '{}'", program))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", program, status);
+    }
+
+    println!("✓ colcon defaults for {}: {}", distro, path.display());
+    println!("  Re-activate {} to pick up new settings", distro);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, home: bool) -> ColconConfig {
+        ColconConfig {
+            dir: dir.to_path_buf(),
+            home,
+        }
+    }
+
+    #[test]
+    fn test_apply_and_clear_colcon() {
+        let dir = std::env::temp_dir().join(format!("rosenv-colcon-{}", std::process::id()));
+        fs::create_dir_all(dir.join("jazzy")).unwrap();
+        fs::write(dir.join("jazzy/defaults.yaml"), "{}").unwrap();
+        let config = config(&dir, true);
+
+        let mut env = EnvMap::new();
+        apply_colcon(&mut env, &config, "jazzy");
+        assert_eq!(
+            env["COLCON_DEFAULTS_FILE"],
+            dir.join("jazzy/defaults.yaml").display().to_string()
+        );
+        assert_eq!(env["COLCON_HOME"], dir.join("jazzy").display().to_string());
+
+        // No defaults.yaml for humble, so only COLCON_HOME moves
+        clear_colcon(&mut env, &config);
+        apply_colcon(&mut env, &config, "humble");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!env.contains_key("COLCON_DEFAULTS_FILE"));
+        assert_eq!(env["COLCON_HOME"], dir.join("humble").display().to_string());
+    }
+
+    #[test]
+    fn test_clear_colcon_keeps_user_settings() {
+        let mut env = EnvMap::from([
+            ("COLCON_HOME".to_string(), "/home/user/.colcon".to_string()),
+            (
+                "COLCON_DEFAULTS_FILE".to_string(),
+                "/cfg/rosenv/colcon/jazzy/defaults.yaml".to_string(),
+            ),
+        ]);
+        clear_colcon(&mut env, &config(Path::new("/cfg/rosenv/colcon"), false));
+
+        assert_eq!(
+            env,
+            EnvMap::from([("COLCON_HOME".to_string(), "/home/user/.colcon".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_apply_colcon_keeps_user_home() {
+        let dir = std::env::temp_dir().join(format!("rosenv-colcon-user-{}", std::process::id()));
+        fs::create_dir_all(dir.join("jazzy")).unwrap();
+        fs::write(dir.join("jazzy/defaults.yaml"), "{}").unwrap();
+        let config = config(&dir, true);
+
+        let user = EnvMap::from([("COLCON_HOME".to_string(), "/home/user/.colcon".to_string())]);
+        let mut env = user.clone();
+        clear_colcon(&mut env, &config);
+        apply_colcon(&mut env, &config, "jazzy");
+        assert_eq!(env["COLCON_HOME"], "/home/user/.colcon");
+        assert!(env.contains_key("COLCON_DEFAULTS_FILE"));
+
+        // Deactivating drops only our defaults file
+        clear_colcon(&mut env, &config);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(env, user);
+    }
+
+    #[test]
+    fn test_colcon_dir_expands_home() {
+        let home = dirs::home_dir().unwrap();
+        let config = config(Path::new("~/colcon"), true);
+
+        assert_eq!(
+            distro_colcon_dir(&config, "jazzy"),
+            home.join("colcon/jazzy")
+        );
+        assert!(is_ours(
+            &config,
+            &home.join("colcon/jazzy").display().to_string()
+        ));
+    }
+}
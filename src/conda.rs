@@ -0,0 +1,221 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CondaRecord {
+    pub name: String,
+    pub version: String,
+    pub build: String,
+}
+
+// An installed package as described by the contents of its record. Reading
+// these is much slower than `read_records`, so only the inventory does it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CondaPackage {
+    pub name: String,
+    pub version: String,
+    pub build: String,
+    pub channel: String,
+}
+
+#[derive(Deserialize)]
+struct RecordJson {
+    name: Option<String>,
+    version: Option<String>,
+    build: Option<String>,
+    channel: Option<String>,
+    subdir: Option<String>,
+}
+
+// `https://conda.anaconda.org/robostack-staging/linux-64` -> `robostack-staging`
+pub fn short_channel(channel: &str, subdir: Option<&str>) -> String {
+    let mut segments: Vec<&str> = channel.split('/').filter(|s| !s.is_empty()).collect();
+    if segments
+        .last()
+        .is_some_and(|last| Some(*last) == subdir || *last == "noarch")
+    {
+        segments.pop();
+    }
+    segments.last().unwrap_or(&channel).to_string()
+}
+
+pub fn parse_record_filename(file_name: &str) -> Option<CondaRecord> {
+    let stem = file_name.strip_suffix(".json")?;
+
+    // Record files are named `{name}-{version}-{build}.json`. Package names may
+    // contain dashes, but versions and build strings never do.
+    let mut parts = stem.rsplitn(3, '-');
+    let build = parts.next()?;
+    let version = parts.next()?;
+    let name = parts.next()?;
+
+    if name.is_empty() || version.is_empty() || build.is_empty() {
+        return None;
+    }
+
+    Some(CondaRecord {
+        name: name.to_string(),
+        version: version.to_string(),
+        build: build.to_string(),
+    })
+}
+
+pub fn read_records(prefix: &Path) -> Vec<CondaRecord> {
+    let conda_meta = prefix.join("conda-meta");
+
+    let mut records = Vec::new();
+    if let Ok(entries) = fs::read_dir(&conda_meta) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(record) = parse_record_filename(&name) {
+                records.push(record);
+            }
+        }
+    }
+
+    records.sort_by(|a, b| a.name.cmp(&b.name));
+    records
+}
+
+pub fn read_packages(prefix: &Path) -> Vec<CondaPackage> {
+    let mut packages = Vec::new();
+    let Ok(entries) = fs::read_dir(prefix.join("conda-meta")) else {
+        return packages;
+    };
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(record) = parse_record_filename(&file_name) else {
+            continue;
+        };
+
+        let json: Option<RecordJson> = fs::read_to_string(entry.path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        let (channel, subdir) = json
+            .as_ref()
+            .map(|j| (j.channel.as_deref(), j.subdir.as_deref()))
+            .unwrap_or_default();
+
+        packages.push(CondaPackage {
+            name: json
+                .as_ref()
+                .and_then(|j| j.name.clone())
+                .unwrap_or(record.name),
+            version: json
+                .as_ref()
+                .and_then(|j| j.version.clone())
+                .unwrap_or(record.version),
+            build: json
+                .as_ref()
+                .and_then(|j| j.build.clone())
+                .unwrap_or(record.build),
+            channel: channel
+                .map(|c| short_channel(c, subdir))
+                .unwrap_or_else(|| "unknown".to_string()),
+        });
+    }
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+// RoboStack pins the distro with a mutex package whose build string starts
+// with the distro name, e.g. `ros2-distro-mutex-0.5.0-jazzy_7.json`
+pub fn mutex_distro(prefix: &Path) -> Option<String> {
+    read_records(prefix)
+        .into_iter()
+        .find(|r| r.name == "ros2-distro-mutex" || r.name == "ros-distro-mutex")
+        .and_then(|r| r.build.split('_').next().map(String::from))
+        .filter(|distro| !distro.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record_filename() {
+        let record = parse_record_filename("libgz-sim8-8.6.0-h1234567_2.json").unwrap();
+        assert_eq!(record.name, "libgz-sim8");
+        assert_eq!(record.version, "8.6.0");
+        assert_eq!(record.build, "h1234567_2");
+    }
+
+    #[test]
+    fn test_parse_record_filename_rejects_other_files() {
+        assert_eq!(parse_record_filename("history"), None);
+        assert_eq!(parse_record_filename("python.json"), None);
+        assert_eq!(parse_record_filename("-1.0-0.json"), None);
+    }
+
+    #[test]
+    fn test_short_channel() {
+        assert_eq!(
+            short_channel(
+                "https://conda.anaconda.org/robostack-staging/linux-64",
+                Some("linux-64")
+            ),
+            "robostack-staging"
+        );
+        assert_eq!(
+            short_channel("https://prefix.dev/conda-forge/", Some("noarch")),
+            "conda-forge"
+        );
+        assert_eq!(short_channel("conda-forge", None), "conda-forge");
+    }
+
+    #[test]
+    fn test_read_packages() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-pkgs-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(
+            conda_meta.join("ros-jazzy-rclcpp-28.1.5-np126py312h2ed9cc7_8.json"),
+            r#"{"name": "ros-jazzy-rclcpp", "version": "28.1.5", "build": "np126py312h2ed9cc7_8",
+                "channel": "https://conda.anaconda.org/robostack-staging/linux-64",
+                "subdir": "linux-64", "files": []}"#,
+        )
+        .unwrap();
+        fs::write(conda_meta.join("zlib-1.3.1-hb9d3cd8_2.json"), "not json").unwrap();
+        fs::write(conda_meta.join("history"), "").unwrap();
+
+        let packages = read_packages(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(
+            packages,
+            vec![
+                CondaPackage {
+                    name: "ros-jazzy-rclcpp".to_string(),
+                    version: "28.1.5".to_string(),
+                    build: "np126py312h2ed9cc7_8".to_string(),
+                    channel: "robostack-staging".to_string(),
+                },
+                CondaPackage {
+                    name: "zlib".to_string(),
+                    version: "1.3.1".to_string(),
+                    build: "hb9d3cd8_2".to_string(),
+                    channel: "unknown".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mutex_distro() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-mutex-{}", std::process::id()));
+        fs::create_dir_all(prefix.join("conda-meta")).unwrap();
+        fs::write(
+            prefix.join("conda-meta/ros2-distro-mutex-0.6.0-humble_14.json"),
+            "{}",
+        )
+        .unwrap();
+
+        let distro = mutex_distro(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(distro.as_deref(), Some("humble"));
+    }
+}
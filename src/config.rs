@@ -0,0 +1,220 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_ROS_MASTER_URI: &str = "http://localhost:11311";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub ros1: Ros1Config,
+    pub colcon: ColconConfig,
+    pub overlays: OverlayConfig,
+    pub discovery: DiscoveryConfig,
+    pub eol: EolConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Ros1Config {
+    pub master_uri: String,
+}
+
+impl Default for Ros1Config {
+    fn default() -> Self {
+        Ros1Config {
+            master_uri: DEFAULT_ROS_MASTER_URI.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ColconConfig {
+    // Holds one `<distro>/defaults.yaml` per distribution
+    pub dir: PathBuf,
+    // Also point COLCON_HOME at the distribution's directory
+    pub home: bool,
+}
+
+impl Default for ColconConfig {
+    fn default() -> Self {
+        ColconConfig {
+            dir: get_colcon_dir(),
+            home: false,
+        }
+    }
+}
+
+// What to do with an overlay that was built against another distribution
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MismatchPolicy {
+    Ignore,
+    #[default]
+    Warn,
+    Refuse,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    pub on_mismatch: MismatchPolicy,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EolMode {
+    Off,
+    #[default]
+    Warn,
+    // Refuse to activate distributions past end of life, e.g. in CI
+    Fail,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct EolConfig {
+    pub mode: EolMode,
+    // Start warning this many days before end of life
+    pub warn_days: u32,
+}
+
+impl Default for EolConfig {
+    fn default() -> Self {
+        EolConfig {
+            mode: EolMode::Warn,
+            warn_days: 90,
+        }
+    }
+}
+
+// Installations outside ~/.pixi/envs that `setup` should pick up
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryConfig {
+    // Extra directories holding conda/micromamba envs, one env per subdir
+    pub conda_envs_dirs: Vec<PathBuf>,
+    // colcon install spaces of ROS built from source
+    pub source_builds: Vec<PathBuf>,
+}
+
+// Paths in config.toml may start with `~/`
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+pub fn get_config_dir() -> PathBuf {
+    dirs::home_dir()
+        .expect("Could not determine home directory")
+        .join(".config")
+        .join("rosenv")
+}
+
+pub fn get_hooks_dir() -> PathBuf {
+    get_config_dir().join("hooks")
+}
+
+pub fn get_colcon_dir() -> PathBuf {
+    get_config_dir().join("colcon")
+}
+
+pub fn get_config_file() -> PathBuf {
+    get_config_dir().join("config.toml")
+}
+
+pub fn parse_config(content: &str) -> Result<Config> {
+    Ok(toml::from_str(content)?)
+}
+
+pub fn load_config() -> Result<Config> {
+    let path = get_config_file();
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content =
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    parse_config(&content).context(format!("Failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_hooks_dir() {
+        let hooks_dir = get_hooks_dir();
+        assert!(hooks_dir.ends_with(".config/rosenv/hooks"));
+    }
+
+    #[test]
+    fn test_parse_config_defaults() {
+        let config = parse_config("").unwrap();
+        assert_eq!(config.ros1.master_uri, DEFAULT_ROS_MASTER_URI);
+    }
+
+    #[test]
+    fn test_parse_config_ros1() {
+        let config = parse_config("[ros1]\nmaster_uri = \"http://robot:11311\"\n").unwrap();
+        assert_eq!(config.ros1.master_uri, "http://robot:11311");
+    }
+
+    #[test]
+    fn test_parse_config_colcon() {
+        let config = parse_config("").unwrap();
+        assert!(config.colcon.dir.ends_with(".config/rosenv/colcon"));
+        assert!(!config.colcon.home);
+
+        let config = parse_config("[colcon]\nhome = true\n").unwrap();
+        assert!(config.colcon.home);
+    }
+
+    #[test]
+    fn test_parse_config_overlays() {
+        assert_eq!(
+            parse_config("").unwrap().overlays.on_mismatch,
+            MismatchPolicy::Warn
+        );
+
+        let config = parse_config("[overlays]\non_mismatch = \"refuse\"\n").unwrap();
+        assert_eq!(config.overlays.on_mismatch, MismatchPolicy::Refuse);
+        assert!(parse_config("[overlays]\non_mismatch = \"maybe\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_config_discovery() {
+        let config = parse_config(
+            "[discovery]\nconda_envs_dirs = [\"~/micromamba/envs\"]\nsource_builds = [\"/ws/ros2/install\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.discovery.source_builds,
+            vec![PathBuf::from("/ws/ros2/install")]
+        );
+        let expanded = expand_home(&config.discovery.conda_envs_dirs[0]);
+        assert!(expanded.is_absolute());
+        assert!(expanded.ends_with("micromamba/envs"));
+    }
+
+    #[test]
+    fn test_parse_config_eol() {
+        let config = parse_config("").unwrap();
+        assert_eq!(config.eol.mode, EolMode::Warn);
+        assert_eq!(config.eol.warn_days, 90);
+
+        let config = parse_config("[eol]\nmode = \"fail\"\nwarn_days = 30\n").unwrap();
+        assert_eq!(config.eol.mode, EolMode::Fail);
+        assert_eq!(config.eol.warn_days, 30);
+    }
+
+    #[test]
+    fn test_parse_config_rejects_invalid() {
+        assert!(parse_config("[ros1\n").is_err());
+    }
+}
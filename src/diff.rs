@@ -0,0 +1,323 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::ament::{read_ament_packages, resource_names};
+use crate::conda::read_packages;
+use crate::distro::validate_distro;
+use crate::env::site_packages_dirs;
+
+// Package name to version, when one is known
+type Inventory = BTreeMap<String, Option<String>>;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct VersionChange {
+    pub name: String,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SectionDiff {
+    pub only_a: Vec<Entry>,
+    pub only_b: Vec<Entry>,
+    pub changed: Vec<VersionChange>,
+}
+
+impl SectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_a.is_empty() && self.only_b.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DistroDiff {
+    pub a: String,
+    pub b: String,
+    pub ament: SectionDiff,
+    pub conda: SectionDiff,
+    pub executables: SectionDiff,
+    pub python: SectionDiff,
+    pub interfaces: SectionDiff,
+}
+
+pub fn diff_inventories(a: &Inventory, b: &Inventory) -> SectionDiff {
+    let mut diff = SectionDiff::default();
+
+    for (name, version) in a {
+        match b.get(name) {
+            None => diff.only_a.push(Entry {
+                name: name.clone(),
+                version: version.clone(),
+            }),
+            Some(other) => {
+                if let (Some(a), Some(b)) = (version, other) {
+                    if a != b {
+                        diff.changed.push(VersionChange {
+                            name: name.clone(),
+                            a: a.clone(),
+                            b: b.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    for (name, version) in b {
+        if !a.contains_key(name) {
+            diff.only_b.push(Entry {
+                name: name.clone(),
+                version: version.clone(),
+            });
+        }
+    }
+
+    diff
+}
+
+fn ament_inventory(prefix: &Path) -> Inventory {
+    read_ament_packages(prefix)
+        .into_iter()
+        .map(|p| (p.name, p.version))
+        .collect()
+}
+
+// ROS packages carry the distro in their conda name, so `ros-humble-rclcpp`
+// and `ros-jazzy-rclcpp` both become `ros-*-rclcpp`
+fn conda_inventory(prefix: &Path, distro: &str) -> Inventory {
+    let ros_prefix = format!("ros-{}-", distro);
+    read_packages(prefix)
+        .into_iter()
+        .map(|p| {
+            let name = match p.name.strip_prefix(&ros_prefix) {
+                Some(rest) => format!("ros-*-{}", rest),
+                None => p.name,
+            };
+            (name, Some(p.version))
+        })
+        .collect()
+}
+
+fn executable_inventory(prefix: &Path) -> Inventory {
+    fs::read_dir(prefix.join("bin"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| (entry.file_name().to_string_lossy().to_string(), None))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// `rclpy-7.1.1.dist-info` or `rclpy-7.1.1-py3.12.egg-info` -> (rclpy, 7.1.1)
+fn parse_python_metadata(dir_name: &str) -> Option<(String, String)> {
+    let stem = dir_name
+        .strip_suffix(".dist-info")
+        .or_else(|| dir_name.strip_suffix(".egg-info"))?;
+    let mut parts = stem.split('-');
+    let name = parts.next().filter(|n| !n.is_empty())?;
+    let version = parts.next().filter(|v| !v.is_empty())?;
+    Some((
+        name.to_lowercase().replace(['-', '.'], "_"),
+        version.to_string(),
+    ))
+}
+
+fn python_inventory(prefix: &Path) -> Inventory {
+    let mut inventory = Inventory::new();
+    for dir in site_packages_dirs(prefix) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Some((name, version)) =
+                parse_python_metadata(&entry.file_name().to_string_lossy())
+            {
+                inventory.insert(name, Some(version));
+            }
+        }
+    }
+    inventory
+}
+
+fn interface_inventory(prefix: &Path, ament: &Inventory) -> Inventory {
+    resource_names(prefix, "rosidl_interfaces")
+        .into_iter()
+        .map(|name| {
+            let version = ament.get(&name).cloned().flatten();
+            (name, version)
+        })
+        .collect()
+}
+
+pub fn diff_distros(a: &str, a_path: &Path, b: &str, b_path: &Path) -> DistroDiff {
+    let ament_a = ament_inventory(a_path);
+    let ament_b = ament_inventory(b_path);
+
+    DistroDiff {
+        a: a.to_string(),
+        b: b.to_string(),
+        ament: diff_inventories(&ament_a, &ament_b),
+        conda: diff_inventories(&conda_inventory(a_path, a), &conda_inventory(b_path, b)),
+        executables: diff_inventories(&executable_inventory(a_path), &executable_inventory(b_path)),
+        python: diff_inventories(&python_inventory(a_path), &python_inventory(b_path)),
+        interfaces: diff_inventories(
+            &interface_inventory(a_path, &ament_a),
+            &interface_inventory(b_path, &ament_b),
+        ),
+    }
+}
+
+fn print_section(title: &str, diff: &SectionDiff, a: &str, b: &str) {
+    if diff.is_empty() {
+        println!("{}: no differences\n", title);
+        return;
+    }
+
+    println!(
+        "{}: {} only in {}, {} only in {}, {} changed",
+        title,
+        diff.only_a.len(),
+        a,
+        diff.only_b.len(),
+        b,
+        diff.changed.len()
+    );
+
+    let width = diff
+        .only_a
+        .iter()
+        .chain(&diff.only_b)
+        .map(|e| e.name.len())
+        .chain(diff.changed.iter().map(|c| c.name.len()))
+        .max()
+        .unwrap_or(0);
+
+    for (sign, entry) in diff
+        .only_a
+        .iter()
+        .map(|e| ('-', e))
+        .chain(diff.only_b.iter().map(|e| ('+', e)))
+    {
+        let line = format!(
+            "  {} {:<width$}  {}",
+            sign,
+            entry.name,
+            entry.version.as_deref().unwrap_or("")
+        );
+        println!("{}", line.trim_end());
+    }
+    for change in &diff.changed {
+        println!("  ~ {:<width$}  {} → {}", change.name, change.a, change.b);
+    }
+    println!();
+}
+
+pub fn cmd_diff(a: &str, b: &str, json: bool) -> Result<()> {
+    let a_path = validate_distro(a)?;
+    let b_path = validate_distro(b)?;
+    let diff = diff_distros(a, &a_path, b, &b_path);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!("Comparing {} (-) with {} (+)\n", a, b);
+    print_section("ament packages", &diff.ament, a, b);
+    print_section("conda packages", &diff.conda, a, b);
+    print_section("executables", &diff.executables, a, b);
+    print_section("Python packages", &diff.python, a, b);
+    print_section("interface packages", &diff.interfaces, a, b);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(entries: &[(&str, Option<&str>)]) -> Inventory {
+        entries
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.map(String::from)))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_inventories() {
+        let a = inventory(&[
+            ("rclcpp", Some("16.0.11")),
+            ("ros2bag", None),
+            ("rosidl_cmake", Some("3.1.5")),
+        ]);
+        let b = inventory(&[
+            ("rclcpp", Some("28.1.5")),
+            ("ros2bag", None),
+            ("rosidl_core_generators", Some("0.2.0")),
+        ]);
+
+        let diff = diff_inventories(&a, &b);
+        assert_eq!(
+            diff.only_a,
+            vec![Entry {
+                name: "rosidl_cmake".to_string(),
+                version: Some("3.1.5".to_string()),
+            }]
+        );
+        assert_eq!(diff.only_b[0].name, "rosidl_core_generators");
+        assert_eq!(
+            diff.changed,
+            vec![VersionChange {
+                name: "rclcpp".to_string(),
+                a: "16.0.11".to_string(),
+                b: "28.1.5".to_string(),
+            }]
+        );
+        assert!(diff_inventories(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn test_parse_python_metadata() {
+        assert_eq!(
+            parse_python_metadata("rclpy-7.1.1.dist-info"),
+            Some(("rclpy".to_string(), "7.1.1".to_string()))
+        );
+        assert_eq!(
+            parse_python_metadata("launch_ros-0.26.5-py3.12.egg-info"),
+            Some(("launch_ros".to_string(), "0.26.5".to_string()))
+        );
+        assert_eq!(parse_python_metadata("rclpy"), None);
+    }
+
+    #[test]
+    fn test_diff_distros() {
+        let root = std::env::temp_dir().join(format!("rosenv-diff-{}", std::process::id()));
+        for (distro, record, exe) in [
+            ("humble", "ros-humble-rclcpp-16.0.11-h1_0.json", "ros2"),
+            ("jazzy", "ros-jazzy-rclcpp-28.1.5-h1_0.json", "ros2"),
+        ] {
+            let prefix = root.join(distro);
+            fs::create_dir_all(prefix.join("conda-meta")).unwrap();
+            fs::write(prefix.join("conda-meta").join(record), "{}").unwrap();
+            fs::create_dir_all(prefix.join("bin")).unwrap();
+            fs::write(prefix.join("bin").join(exe), "").unwrap();
+        }
+        fs::write(root.join("jazzy/bin/gz"), "").unwrap();
+
+        let diff = diff_distros("humble", &root.join("humble"), "jazzy", &root.join("jazzy"));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(diff.conda.changed[0].name, "ros-*-rclcpp");
+        assert!(diff.conda.only_a.is_empty() && diff.conda.only_b.is_empty());
+        assert_eq!(diff.executables.only_b[0].name, "gz");
+        assert!(diff.ament.is_empty());
+    }
+}
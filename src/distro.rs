@@ -1,22 +1,100 @@
 use anyhow::{Context, Result};
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::conda::{mutex_distro, read_records};
+use crate::pixi_global::{load_global_manifest, GlobalEnv};
+use crate::registry::lookup;
+
+// REP 150 variants, most complete first
+const VARIANTS: [&str; 4] = ["desktop-full", "desktop", "ros-base", "ros-core"];
+
+// Where an installation comes from, which decides how it is found and managed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    PixiGlobal,
+    Apt,
+    CondaEnv,
+    SourceBuild,
+    Manual,
+}
+
+impl SourceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceKind::PixiGlobal => "pixi-global",
+            SourceKind::Apt => "apt",
+            SourceKind::CondaEnv => "conda-env",
+            SourceKind::SourceBuild => "source-build",
+            SourceKind::Manual => "manual",
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Distribution {
     pub name: String,
     pub path: PathBuf,
+    pub kind: SourceKind,
+    // How pixi-global.toml declares the env; None if it is not listed there
+    pub declared: Option<GlobalEnv>,
 }
 
 pub fn get_ros_root() -> PathBuf {
     PathBuf::from("/opt/ros")
 }
 
+// PIXI_HOME wins when set and non-empty, like pixi itself
+fn pixi_home_from(pixi_home: Option<OsString>) -> PathBuf {
+    match pixi_home {
+        Some(home) if !home.is_empty() => PathBuf::from(home),
+        _ => dirs::home_dir()
+            .expect("Could not determine home directory")
+            .join(".pixi"),
+    }
+}
+
+pub fn get_pixi_home() -> PathBuf {
+    pixi_home_from(std::env::var_os("PIXI_HOME"))
+}
+
 pub fn get_pixi_envs_dir() -> PathBuf {
-    dirs::home_dir()
-        .expect("Could not determine home directory")
-        .join(".pixi")
-        .join("envs")
+    get_pixi_home().join("envs")
+}
+
+fn valid_name(distro: String) -> Option<String> {
+    validate_distro_name(&distro).ok().map(|_| distro)
+}
+
+// The ROS distro installed in a conda prefix, judged by its content: the
+// RoboStack mutex package, or else the `ros-<distro>-ros-environment` package
+// every distro ships. Only if neither exists is a `ros-<distro>-*` env name
+// of a known distro with setup files trusted.
+pub fn detect_env_distro(prefix: &Path, env_name: &str) -> Option<String> {
+    if let Some(distro) = mutex_distro(prefix).and_then(valid_name) {
+        return Some(distro);
+    }
+
+    let from_records = read_records(prefix).into_iter().find_map(|r| {
+        r.name
+            .strip_prefix("ros-")?
+            .strip_suffix("-ros-environment")
+            .map(String::from)
+    });
+    if let Some(distro) = from_records.and_then(valid_name) {
+        return Some(distro);
+    }
+
+    let has_setup = prefix.join("setup.bash").exists() || prefix.join("setup.zsh").exists();
+    if !has_setup {
+        return None;
+    }
+    env_name
+        .strip_prefix("ros-")
+        .and_then(|rest| rest.split('-').next())
+        .filter(|name| lookup(name).is_some())
+        .map(String::from)
 }
 
 pub fn scan_pixi_ros_installations() -> Result<Vec<Distribution>> {
@@ -26,6 +104,14 @@ pub fn scan_pixi_ros_installations() -> Result<Vec<Distribution>> {
         return Ok(Vec::new());
     }
 
+    let manifest = match load_global_manifest() {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Warning: {:#}", e);
+            None
+        }
+    };
+
     let mut distributions = Vec::new();
 
     for entry in fs::read_dir(&pixi_envs).context("Failed to read ~/.pixi/envs")? {
@@ -38,21 +124,16 @@ pub fn scan_pixi_ros_installations() -> Result<Vec<Distribution>> {
 
         let dir_name = path.file_name().unwrap().to_string_lossy();
 
-        if dir_name.starts_with("ros-") {
-            let parts: Vec<&str> = dir_name.split('-').collect();
-            if parts.len() >= 2 {
-                let distro_name = parts[1].to_string();
-
-                let setup_bash = path.join("setup.bash");
-                let setup_zsh = path.join("setup.zsh");
-
-                if setup_bash.exists() || setup_zsh.exists() {
-                    distributions.push(Distribution {
-                        name: distro_name,
-                        path: path.clone(),
-                    });
-                }
-            }
+        if let Some(distro_name) = detect_env_distro(&path, &dir_name) {
+            let declared = manifest
+                .as_ref()
+                .and_then(|m| m.envs.get(dir_name.as_ref()).cloned());
+            distributions.push(Distribution {
+                name: distro_name,
+                path: path.clone(),
+                kind: SourceKind::PixiGlobal,
+                declared,
+            });
         }
     }
 
@@ -82,12 +163,94 @@ pub fn list_distributions() -> Result<Vec<String>> {
     Ok(distros)
 }
 
+// Known distros go by the registry. For custom names, RoboStack marks ROS 1
+// envs with `ros-distro-mutex` and ROS 2 envs with `ros2-distro-mutex`; other
+// installs are recognized by roscore
+pub fn detect_ros_version(prefix: &Path, distro: &str) -> u8 {
+    if let Some(info) = lookup(distro) {
+        return info.ros_version;
+    }
+
+    let records = read_records(prefix);
+    if records.iter().any(|r| r.name == "ros2-distro-mutex") {
+        return 2;
+    }
+    if records.iter().any(|r| r.name == "ros-distro-mutex") {
+        return 1;
+    }
+
+    if prefix.join("bin/roscore").exists() {
+        1
+    } else {
+        2
+    }
+}
+
+pub fn detect_variant(prefix: &Path, distro: &str) -> Option<String> {
+    let records = read_records(prefix);
+
+    VARIANTS
+        .iter()
+        .find(|variant| {
+            let package = format!("ros-{}-{}", distro, variant);
+            records.iter().any(|r| r.name == package)
+        })
+        .map(|variant| variant.to_string())
+}
+
 pub fn get_current_distro() -> Option<String> {
     std::env::var("ROS_DISTRO").ok()
 }
 
+const MAX_DISTRO_NAME_LEN: usize = 64;
+
+// Distro names end up in paths below /opt/ros and in generated shell code:
+// a lowercase letter followed by lowercase letters, digits, '_' or '-'. This
+// rules out separators, "." and "..", so a name can never leave the ROS root.
+pub fn validate_distro_name(distro: &str) -> Result<()> {
+    let mut chars = distro.chars();
+    let valid = distro.len() <= MAX_DISTRO_NAME_LEN
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if !valid {
+        anyhow::bail!(
+            "Invalid distribution name '{}'\n\nNames start with a lowercase letter and contain only lowercase letters, digits, '_' and '-'",
+            distro.escape_debug()
+        );
+    }
+    Ok(())
+}
+
+fn distro_path_in(root: &Path, distro: &str) -> Result<PathBuf> {
+    validate_distro_name(distro)?;
+
+    let path = root.join(distro);
+
+    // Whatever the entry points to, the entry itself must sit directly in the
+    // ROS root. Compare canonical parents so a symlinked root still matches.
+    let parent = path.parent().unwrap_or(root);
+    if let (Ok(canonical_root), Ok(canonical_parent)) =
+        (fs::canonicalize(root), fs::canonicalize(parent))
+    {
+        if canonical_root != canonical_parent {
+            anyhow::bail!(
+                "Distribution '{}' resolves outside {}",
+                distro,
+                root.display()
+            );
+        }
+    }
+
+    Ok(path)
+}
+
+// Path of the /opt/ros entry for a distro, which need not exist yet
+pub fn distro_link_path(distro: &str) -> Result<PathBuf> {
+    distro_path_in(&get_ros_root(), distro)
+}
+
 pub fn validate_distro(distro: &str) -> Result<PathBuf> {
-    let path = get_ros_root().join(distro);
+    let path = distro_link_path(distro)?;
     if !path.exists() {
         anyhow::bail!("Distribution '{}' not found in /opt/ros", distro);
     }
@@ -103,12 +266,54 @@ mod tests {
         let distro = Distribution {
             name: "humble".to_string(),
             path: PathBuf::from("/test/path"),
+            kind: SourceKind::Apt,
+            declared: None,
         };
 
         assert_eq!(distro.name, "humble");
         assert_eq!(distro.path, PathBuf::from("/test/path"));
     }
 
+    #[test]
+    fn test_detect_env_distro() {
+        let envs = std::env::temp_dir().join(format!("rosenv-envs-{}", std::process::id()));
+        let robot = envs.join("robot-stack");
+        let sim = envs.join("sim");
+        let named = envs.join("ros-humble-desktop");
+        let tools = envs.join("tools");
+        for env in [&robot, &sim, &named, &tools] {
+            fs::create_dir_all(env.join("conda-meta")).unwrap();
+        }
+        fs::write(
+            robot.join("conda-meta/ros2-distro-mutex-0.6.0-jazzy_7.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(
+            sim.join("conda-meta/ros-noetic-ros-environment-1.3.2-py311_3.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(named.join("setup.bash"), "").unwrap();
+        fs::write(tools.join("conda-meta/ripgrep-14.1.0-h1_0.json"), "{}").unwrap();
+
+        let detected: Vec<_> = [&robot, &sim, &named, &tools]
+            .iter()
+            .map(|env| detect_env_distro(env, &env.file_name().unwrap().to_string_lossy()))
+            .collect();
+        fs::remove_dir_all(&envs).unwrap();
+
+        assert_eq!(
+            detected,
+            vec![
+                Some("jazzy".to_string()),
+                Some("noetic".to_string()),
+                Some("humble".to_string()),
+                None
+            ]
+        );
+    }
+
     #[test]
     fn test_get_ros_root() {
         let root = get_ros_root();
@@ -117,9 +322,119 @@ mod tests {
 
     #[test]
     fn test_get_pixi_envs_dir() {
+        let default = dirs::home_dir().unwrap().join(".pixi");
+        assert_eq!(pixi_home_from(None), default);
+        assert_eq!(pixi_home_from(Some(OsString::new())), default);
+        assert_eq!(
+            pixi_home_from(Some(OsString::from("/data/pixi"))),
+            PathBuf::from("/data/pixi")
+        );
+
         let envs_dir = get_pixi_envs_dir();
-        assert!(envs_dir.to_string_lossy().contains(".pixi"));
-        assert!(envs_dir.to_string_lossy().contains("envs"));
+        assert!(envs_dir.ends_with("envs"));
+    }
+
+    #[test]
+    fn test_detect_ros_version() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-version-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(conda_meta.join("ros-distro-mutex-0.5.0-noetic.json"), "{}").unwrap();
+
+        let legacy = detect_ros_version(&prefix, "robot");
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(legacy, 1);
+        assert_eq!(detect_ros_version(Path::new("/nonexistent"), "noetic"), 1);
+        assert_eq!(detect_ros_version(Path::new("/nonexistent"), "jazzy"), 2);
+    }
+
+    #[test]
+    fn test_detect_variant() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-variant-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(
+            conda_meta.join("ros-jazzy-ros-base-0.11.0-np126py312_11.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(
+            conda_meta.join("ros-jazzy-desktop-0.11.0-np126py312_11.json"),
+            "{}",
+        )
+        .unwrap();
+
+        let variant = detect_variant(&prefix, "jazzy");
+        let other = detect_variant(&prefix, "humble");
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(variant, Some("desktop".to_string()));
+        assert_eq!(other, None);
+    }
+
+    #[test]
+    fn test_validate_distro_name() {
+        assert!(validate_distro_name("humble").is_ok());
+        assert!(validate_distro_name("ros-jazzy_2").is_ok());
+
+        for name in ["", "jazzy;id", "a b", "$(id)", "it's", "../etc", "x\ny"] {
+            assert!(validate_distro_name(name).is_err(), "accepted {:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_distro_name_rejects_traversal() {
+        let long = "a".repeat(MAX_DISTRO_NAME_LEN + 1);
+        let hostile = [
+            "..",
+            ".",
+            "../../usr",
+            "../..",
+            "/etc",
+            "/",
+            "humble/../..",
+            "humble/",
+            "a/b",
+            "..\\..",
+            "%2e%2e",
+            ".hidden",
+            "-rf",
+            "_x",
+            "9lives",
+            "Humble",
+            " humble",
+            "humble ",
+            "humble\0",
+            "hümble",
+            "~",
+            "~root",
+            "*",
+            long.as_str(),
+        ];
+
+        for name in hostile {
+            assert!(validate_distro_name(name).is_err(), "accepted {:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_distro_path_in_stays_in_root() {
+        let root = std::env::temp_dir().join(format!("rosenv-root-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        std::os::unix::fs::symlink("/usr", root.join("usr")).unwrap();
+
+        let valid = distro_path_in(&root, "humble");
+        let linked = distro_path_in(&root, "usr");
+        let traversal = distro_path_in(&root, "../../usr");
+        let absolute = distro_path_in(&root, "/usr");
+        fs::remove_dir_all(&root).unwrap();
+
+        // The link itself lives in the root even though it points elsewhere
+        assert_eq!(valid.unwrap(), root.join("humble"));
+        assert_eq!(linked.unwrap(), root.join("usr"));
+        assert!(traversal.is_err());
+        assert!(absolute.is_err());
     }
 
     #[test]
@@ -1,7 +1,11 @@
 use anyhow::Result;
 use std::fs;
 
-use crate::distro::{get_ros_root, list_distributions};
+use crate::config::{load_config, EolMode};
+use crate::distro::{get_ros_root, list_distributions, SourceKind};
+use crate::eol::{effective_mode, eol_message, eol_status};
+use crate::registry::{Date, EolStatus};
+use crate::sources::installed_distributions;
 use crate::symlink::check_opt_ros_writable;
 
 pub fn cmd_doctor() -> Result<()> {
@@ -11,6 +15,8 @@ pub fn cmd_doctor() -> Result<()> {
     let mut warnings = 0;
 
     let ros_root = get_ros_root();
+    let eol_config = load_config()?.eol;
+    let eol_mode = effective_mode(&eol_config)?;
     if !ros_root.exists() {
         println!("✗ /opt/ros directory does not exist");
         println!("  Fix: sudo mkdir -p /opt/ros && sudo chown $USER /opt/ros\n");
@@ -36,50 +42,80 @@ pub fn cmd_doctor() -> Result<()> {
     } else {
         println!("✓ Found {} distributions in /opt/ros\n", distros.len());
 
-        for distro in &distros {
-            println!("Distribution: {}", distro);
-            let distro_path = ros_root.join(distro);
-
-            if distro_path.is_symlink() {
-                println!("  ✓ Symlink valid");
+        for distro in &installed_distributions()? {
+            println!("Distribution: {} ({})", distro.name, distro.kind.name());
+            let distro_path = ros_root.join(&distro.name);
 
+            let target = if distro_path.is_symlink() {
                 match fs::read_link(&distro_path) {
                     Ok(target) => {
-                        if target.exists() {
-                            println!("  ✓ Target exists: {}", target.display());
-                        } else {
+                        println!("  ✓ Symlink valid");
+                        if !target.exists() {
                             println!("  ✗ Target does not exist: {}", target.display());
-                            println!("    Fix: rosenv remove {} && rosenv setup", distro);
+                            println!("    Fix: rosenv remove {} && rosenv setup", distro.name);
                             errors += 1;
+                            println!();
+                            continue;
                         }
-
-                        let setup_zsh = target.join("setup.zsh");
-                        let setup_bash = target.join("setup.bash");
-                        if setup_zsh.exists() || setup_bash.exists() {
-                            println!("  ✓ Setup files present");
-                        } else {
-                            println!("  ✗ Setup files missing");
-                            errors += 1;
-                        }
-
-                        let bin_dir = target.join("bin");
-                        let lib_dir = target.join("lib");
-                        if bin_dir.exists() && lib_dir.exists() {
-                            println!("  ✓ Binary and library directories exist");
-                        } else {
-                            println!("  ⚠ Some directories missing");
-                            warnings += 1;
-                        }
+                        println!("  ✓ Target exists: {}", target.display());
+                        target
                     }
                     Err(_) => {
                         println!("  ✗ Could not read symlink");
                         errors += 1;
+                        println!();
+                        continue;
                     }
                 }
             } else {
-                println!("  ⚠ Not a symlink (regular directory)");
+                match distro.kind {
+                    SourceKind::Manual => {
+                        println!(
+                            "  ⚠ Not from pixi, apt, conda or a colcon build (manual install)"
+                        );
+                        warnings += 1;
+                    }
+                    kind => println!("  ✓ {} installation in place", kind.name()),
+                }
+                distro_path.clone()
+            };
+
+            let setup_zsh = target.join("setup.zsh");
+            let setup_bash = target.join("setup.bash");
+            if setup_zsh.exists() || setup_bash.exists() {
+                println!("  ✓ Setup files present");
+            } else {
+                println!("  ✗ Setup files missing");
+                errors += 1;
+            }
+
+            let bin_dir = target.join("bin");
+            let lib_dir = target.join("lib");
+            if bin_dir.exists() && lib_dir.exists() {
+                println!("  ✓ Binary and library directories exist");
+            } else {
+                println!("  ⚠ Some directories missing");
                 warnings += 1;
             }
+
+            let status = eol_status(&distro.name, &eol_config, Date::today());
+            match (eol_mode, status) {
+                (EolMode::Off, _) | (_, EolStatus::Supported) => {}
+                (EolMode::Fail, EolStatus::EndOfLife(_)) => {
+                    println!(
+                        "  ✗ {}",
+                        eol_message(&distro.name, status).unwrap_or_default()
+                    );
+                    errors += 1;
+                }
+                _ => {
+                    println!(
+                        "  ⚠ {}",
+                        eol_message(&distro.name, status).unwrap_or_default()
+                    );
+                    warnings += 1;
+                }
+            }
             println!();
         }
     }
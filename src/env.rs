@@ -0,0 +1,771 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::colcon::{apply_colcon, clear_colcon, COLCON_VARS};
+use crate::config::Config;
+use crate::distro::detect_ros_version;
+use crate::gazebo::{detect_gazebo, GazeboRelease};
+use crate::overlay::overlay_prefixes;
+use crate::python::detect_python;
+use crate::registry::{lookup, DiscoveryStyle};
+
+pub type EnvMap = BTreeMap<String, String>;
+
+// Variables holding colon-separated path lists that activation manipulates
+pub const PATH_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "PYTHONPATH",
+    "PKG_CONFIG_PATH",
+    "CMAKE_PREFIX_PATH",
+    "AMENT_PREFIX_PATH",
+    "COLCON_PREFIX_PATH",
+    "GZ_SIM_RESOURCE_PATH",
+    "GZ_SIM_SYSTEM_PLUGIN_PATH",
+    "GZ_GUI_PLUGIN_PATH",
+    "IGN_GAZEBO_RESOURCE_PATH",
+    "IGN_GAZEBO_SYSTEM_PLUGIN_PATH",
+    "IGN_GUI_PLUGIN_PATH",
+    "ROS_PACKAGE_PATH",
+];
+
+// Scalar variables activation sets on top of the path lists
+const SCALAR_VARS: &[&str] = &[
+    "ROS_DISTRO",
+    "ROS_VERSION",
+    "ROS_PYTHON_VERSION",
+    "GZ_VERSION",
+    "ROS_ROOT",
+    "ROS_ETC_DIR",
+    "ROS_MASTER_URI",
+    "COLCON_DEFAULTS_FILE",
+    "COLCON_HOME",
+];
+
+// Variables kept from the caller when starting from a clean environment
+const CLEAN_WHITELIST: &[&str] = &[
+    "HOME", "USER", "LOGNAME", "SHELL", "TERM", "LANG", "LC_ALL", "TZ", "TMPDIR",
+];
+
+const CLEAN_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+
+const STRIPPED_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "PYTHONPATH",
+    "PKG_CONFIG_PATH",
+    "CMAKE_PREFIX_PATH",
+    "AMENT_PREFIX_PATH",
+];
+
+const GAZEBO_PATH_VARS: &[&str] = &[
+    "GZ_SIM_RESOURCE_PATH",
+    "GZ_SIM_SYSTEM_PLUGIN_PATH",
+    "GZ_GUI_PLUGIN_PATH",
+    "IGN_GAZEBO_RESOURCE_PATH",
+    "IGN_GAZEBO_SYSTEM_PLUGIN_PATH",
+    "IGN_GUI_PLUGIN_PATH",
+];
+
+pub fn current_env() -> EnvMap {
+    std::env::vars().collect()
+}
+
+pub fn clean_env() -> EnvMap {
+    let mut env: EnvMap = std::env::vars()
+        .filter(|(name, _)| CLEAN_WHITELIST.contains(&name.as_str()))
+        .collect();
+    env.insert("PATH".to_string(), CLEAN_PATH.to_string());
+    env
+}
+
+pub fn is_path_var(name: &str) -> bool {
+    PATH_VARS.contains(&name)
+}
+
+pub fn is_managed_var(name: &str) -> bool {
+    is_path_var(name) || SCALAR_VARS.contains(&name)
+}
+
+// The subset of an environment that activation is responsible for
+pub fn managed_vars(env: &EnvMap) -> EnvMap {
+    env.iter()
+        .filter(|(name, _)| is_managed_var(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+fn split_path(value: &str) -> Vec<&str> {
+    value.split(':').filter(|entry| !entry.is_empty()).collect()
+}
+
+// Variables users also set themselves: deactivation only takes back what
+// rosenv added instead of unsetting them
+pub const SHARED_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "COLCON_DEFAULTS_FILE",
+    "COLCON_HOME",
+    "ROS_MASTER_URI",
+];
+
+// A master URI the user exported points at their robot; only the configured
+// default is ours to remove
+fn clear_master_uri(env: &mut EnvMap, config: &Config) {
+    if env.get("ROS_MASTER_URI") == Some(&config.ros1.master_uri) {
+        env.remove("ROS_MASTER_URI");
+    }
+}
+
+pub fn managed_var_names() -> impl Iterator<Item = &'static str> {
+    PATH_VARS.iter().chain(SCALAR_VARS.iter()).copied()
+}
+
+// Drop every entry below /opt/ros/ so no other distro leaks in
+fn strip_ros_entries(value: &str) -> String {
+    value
+        .split(':')
+        .filter(|entry| !entry.contains("/opt/ros/"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn strip_var(env: &mut EnvMap, var: &str) {
+    if let Some(value) = env.get(var) {
+        let stripped = strip_ros_entries(value);
+        if stripped.is_empty() {
+            env.remove(var);
+        } else {
+            env.insert(var.to_string(), stripped);
+        }
+    }
+}
+
+// Only existing directories are added, and never twice
+fn append_path(env: &mut EnvMap, var: &str, dir: &Path) {
+    if !dir.is_dir() {
+        return;
+    }
+
+    let dir = dir.display().to_string();
+    match env.get(var) {
+        Some(current) if !current.is_empty() => {
+            if !current.split(':').any(|entry| entry == dir) {
+                let value = format!("{}:{}", current, dir);
+                env.insert(var.to_string(), value);
+            }
+        }
+        _ => {
+            env.insert(var.to_string(), dir);
+        }
+    }
+}
+
+// Overlays take precedence, so their entries move to the front
+pub fn prepend_path(env: &mut EnvMap, var: &str, dir: &Path) {
+    if !dir.is_dir() {
+        return;
+    }
+
+    let dir = dir.display().to_string();
+    let mut entries = vec![dir.as_str()];
+    if let Some(current) = env.get(var) {
+        entries.extend(
+            split_path(current)
+                .into_iter()
+                .filter(|entry| *entry != dir),
+        );
+    }
+    let value = entries.join(":");
+    env.insert(var.to_string(), value);
+}
+
+// Conda and pip use site-packages; Debian packages and colcon builds on top of
+// them use dist-packages, also below local/
+pub fn site_packages_dirs(prefix: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for lib in [prefix.join("lib"), prefix.join("local/lib")] {
+        if let Ok(entries) = fs::read_dir(&lib) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with("python") {
+                    for packages in ["site-packages", "dist-packages"] {
+                        let dir = entry.path().join(packages);
+                        if dir.is_dir() {
+                            dirs.push(dir);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+// Stale lib/python* dirs survive a Python upgrade, so only the interpreter's
+// own site-packages is used when conda-meta says which one that is
+fn prefix_site_packages(prefix: &Path) -> Vec<PathBuf> {
+    match detect_python(prefix) {
+        Some(python) => vec![python.site_packages(prefix)],
+        None => site_packages_dirs(prefix),
+    }
+}
+
+// Appends a distro prefix to the search paths shared by ROS 1 and ROS 2
+fn append_prefix(env: &mut EnvMap, ros_root: &Path) {
+    append_path(env, "CMAKE_PREFIX_PATH", ros_root);
+    append_path(env, "PATH", &ros_root.join("bin"));
+    append_path(env, "PKG_CONFIG_PATH", &ros_root.join("lib/pkgconfig"));
+    for site_packages in prefix_site_packages(ros_root) {
+        append_path(env, "PYTHONPATH", &site_packages);
+    }
+
+    // Conda packages find their libraries through RPATH; apt installs and
+    // source builds rely on the loader path
+    if !ros_root.join("conda-meta").is_dir() {
+        append_path(env, "LD_LIBRARY_PATH", &ros_root.join("lib"));
+    }
+}
+
+// Discovery settings are the user's (ROS_DOMAIN_ID, ROS_HOSTNAME, ...) and
+// survive every switch, whichever generation they belong to; a localhost-only
+// setting is additionally given in the form the distro understands
+fn apply_discovery(env: &mut EnvMap, style: DiscoveryStyle) {
+    let localhost = env.get("ROS_LOCALHOST_ONLY").is_some_and(|v| v == "1")
+        || env
+            .get("ROS_AUTOMATIC_DISCOVERY_RANGE")
+            .is_some_and(|v| v == "LOCALHOST");
+    if !localhost {
+        return;
+    }
+
+    match style {
+        DiscoveryStyle::LocalhostOnly => {
+            env.entry("ROS_LOCALHOST_ONLY".to_string())
+                .or_insert_with(|| "1".to_string());
+        }
+        DiscoveryStyle::DiscoveryRange => {
+            env.entry("ROS_AUTOMATIC_DISCOVERY_RANGE".to_string())
+                .or_insert_with(|| "LOCALHOST".to_string());
+        }
+        DiscoveryStyle::RosMaster => {}
+    }
+}
+
+// The environment after switching `base` to a distro, including cleaning up
+// whatever a previously active distro of either ROS generation left behind
+pub fn compute_activation(distro: &str, ros_root: &Path, base: &EnvMap, config: &Config) -> EnvMap {
+    let mut env = base.clone();
+
+    for var in STRIPPED_VARS {
+        strip_var(&mut env, var);
+    }
+
+    env.remove("GZ_VERSION");
+    for var in GAZEBO_PATH_VARS {
+        strip_var(&mut env, var);
+    }
+
+    for var in ["ROS_ROOT", "ROS_ETC_DIR"] {
+        env.remove(var);
+    }
+    clear_master_uri(&mut env, config);
+    strip_var(&mut env, "ROS_PACKAGE_PATH");
+    clear_colcon(&mut env, &config.colcon);
+    // Custom distro names have no known style; their settings are left alone
+    if let Some(info) = lookup(distro) {
+        apply_discovery(&mut env, info.discovery);
+    }
+
+    let ros_version = detect_ros_version(ros_root, distro);
+    // Isolated colcon installs have one prefix per package
+    let prefixes = overlay_prefixes(ros_root);
+
+    env.insert("ROS_DISTRO".to_string(), distro.to_string());
+    env.insert("ROS_VERSION".to_string(), ros_version.to_string());
+    let python_major = detect_python(ros_root).map_or(3, |python| python.major);
+    env.insert("ROS_PYTHON_VERSION".to_string(), python_major.to_string());
+
+    if ros_version == 1 {
+        let share = ros_root.join("share");
+        env.insert(
+            "ROS_ROOT".to_string(),
+            share.join("ros").display().to_string(),
+        );
+        env.insert(
+            "ROS_ETC_DIR".to_string(),
+            ros_root.join("etc/ros").display().to_string(),
+        );
+        env.entry("ROS_MASTER_URI".to_string())
+            .or_insert_with(|| config.ros1.master_uri.clone());
+        env.remove("AMENT_PREFIX_PATH");
+        for prefix in &prefixes {
+            append_path(&mut env, "ROS_PACKAGE_PATH", &prefix.join("share"));
+        }
+    } else {
+        for prefix in &prefixes {
+            append_path(&mut env, "AMENT_PREFIX_PATH", prefix);
+        }
+    }
+
+    for prefix in &prefixes {
+        append_prefix(&mut env, prefix);
+    }
+
+    if let Some((gazebo, _)) = detect_gazebo(ros_root) {
+        apply_gazebo(&mut env, gazebo, ros_root);
+    }
+
+    apply_colcon(&mut env, &config.colcon, distro);
+
+    env
+}
+
+// Inside a pixi workspace pixi has already set up its own prefix; only stale
+// /opt/ros entries are removed and, if given, the global distro is appended
+pub fn compute_pixi_activation(distro: &str, global_root: Option<&Path>, base: &EnvMap) -> EnvMap {
+    let mut env = base.clone();
+
+    for var in STRIPPED_VARS {
+        strip_var(&mut env, var);
+    }
+
+    env.insert("ROS_DISTRO".to_string(), distro.to_string());
+
+    if let Some(ros_root) = global_root {
+        append_path(&mut env, "AMENT_PREFIX_PATH", ros_root);
+        append_prefix(&mut env, ros_root);
+    }
+
+    env
+}
+
+// Shared path lists keep everything outside /opt/ros, and colcon settings and
+// a master URI the user made themselves survive; all other managed variables go
+pub fn compute_deactivation(base: &EnvMap, config: &Config) -> EnvMap {
+    let mut env = base.clone();
+    for var in managed_var_names() {
+        if COLCON_VARS.contains(&var) || var == "ROS_MASTER_URI" {
+            continue;
+        }
+        if SHARED_VARS.contains(&var) {
+            strip_var(&mut env, var);
+        } else {
+            env.remove(var);
+        }
+    }
+    clear_colcon(&mut env, &config.colcon);
+    clear_master_uri(&mut env, config);
+    env
+}
+
+fn apply_gazebo(env: &mut EnvMap, gazebo: GazeboRelease, ros_root: &Path) {
+    env.insert("GZ_VERSION".to_string(), gazebo.name().to_string());
+    append_path(env, gazebo.resource_path_var(), &ros_root.join("share"));
+    append_path(env, gazebo.system_plugin_path_var(), &ros_root.join("lib"));
+    append_path(env, gazebo.gui_plugin_path_var(), &ros_root.join("lib"));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum VarChange {
+    Added {
+        value: String,
+    },
+    Removed {
+        old: String,
+    },
+    Changed {
+        old: String,
+        new: String,
+    },
+    Path {
+        added: Vec<String>,
+        removed: Vec<String>,
+        reordered: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VarDiff {
+    pub name: String,
+    #[serde(flatten)]
+    pub change: VarChange,
+}
+
+fn diff_path(old: &str, new: &str) -> VarChange {
+    let old_entries = split_path(old);
+    let new_entries = split_path(new);
+
+    let added: Vec<String> = new_entries
+        .iter()
+        .filter(|entry| !old_entries.contains(entry))
+        .map(|entry| entry.to_string())
+        .collect();
+    let removed: Vec<String> = old_entries
+        .iter()
+        .filter(|entry| !new_entries.contains(entry))
+        .map(|entry| entry.to_string())
+        .collect();
+
+    // Entries present on both sides must keep their relative order
+    let kept_old: Vec<&&str> = old_entries
+        .iter()
+        .filter(|entry| new_entries.contains(entry))
+        .collect();
+    let kept_new: Vec<&&str> = new_entries
+        .iter()
+        .filter(|entry| old_entries.contains(entry))
+        .collect();
+
+    VarChange::Path {
+        added,
+        removed,
+        reordered: kept_old != kept_new,
+    }
+}
+
+pub fn diff_env(before: &EnvMap, after: &EnvMap) -> Vec<VarDiff> {
+    let mut diffs = Vec::new();
+
+    for (name, new) in after {
+        let change = match before.get(name) {
+            None => VarChange::Added { value: new.clone() },
+            Some(old) if old == new => continue,
+            Some(old) if is_path_var(name) => diff_path(old, new),
+            Some(old) => VarChange::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+        };
+        diffs.push(VarDiff {
+            name: name.clone(),
+            change,
+        });
+    }
+
+    for (name, old) in before {
+        if !after.contains_key(name) {
+            diffs.push(VarDiff {
+                name: name.clone(),
+                change: VarChange::Removed { old: old.clone() },
+            });
+        }
+    }
+
+    diffs.sort_by(|a, b| a.name.cmp(&b.name));
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> EnvMap {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_strip_ros_entries() {
+        assert_eq!(
+            strip_ros_entries("/usr/bin:/opt/ros/humble/bin:/bin"),
+            "/usr/bin:/bin"
+        );
+        assert_eq!(strip_ros_entries("/opt/ros/humble"), "");
+    }
+
+    #[test]
+    fn test_compute_activation_replaces_previous_distro() {
+        let ros_root = std::env::temp_dir().join(format!("rosenv-env-{}", std::process::id()));
+        fs::create_dir_all(ros_root.join("bin")).unwrap();
+        fs::create_dir_all(ros_root.join("lib/python3.12/site-packages")).unwrap();
+
+        let base = env(&[
+            ("PATH", "/usr/bin:/opt/ros/humble/bin"),
+            ("AMENT_PREFIX_PATH", "/opt/ros/humble"),
+            ("ROS_DISTRO", "humble"),
+            ("GZ_VERSION", "fortress"),
+        ]);
+        let activated = compute_activation("jazzy", &ros_root, &base, &Config::default());
+        fs::remove_dir_all(&ros_root).unwrap();
+
+        let root = ros_root.display().to_string();
+        assert_eq!(activated["ROS_DISTRO"], "jazzy");
+        assert_eq!(activated["ROS_VERSION"], "2");
+        assert_eq!(activated["PATH"], format!("/usr/bin:{}/bin", root));
+        assert_eq!(activated["AMENT_PREFIX_PATH"], root);
+        assert_eq!(
+            activated["PYTHONPATH"],
+            format!("{}/lib/python3.12/site-packages", root)
+        );
+        assert!(!activated.contains_key("PKG_CONFIG_PATH"));
+        assert!(!activated.contains_key("GZ_VERSION"));
+    }
+
+    #[test]
+    fn test_prepend_path_moves_entry_to_front() {
+        let dir = std::env::temp_dir();
+        let dir_str = dir.display().to_string();
+        let mut vars = env(&[("PATH", &format!("/usr/bin:{}:/bin", dir_str))]);
+
+        prepend_path(&mut vars, "PATH", &dir);
+        assert_eq!(vars["PATH"], format!("{}:/usr/bin:/bin", dir_str));
+
+        prepend_path(&mut vars, "PATH", Path::new("/nonexistent/bin"));
+        assert_eq!(vars["PATH"], format!("{}:/usr/bin:/bin", dir_str));
+    }
+
+    #[test]
+    fn test_compute_activation_ros1_cleans_up_ros2() {
+        let ros_root = std::env::temp_dir().join(format!("rosenv-noetic-{}", std::process::id()));
+        fs::create_dir_all(ros_root.join("share/ros")).unwrap();
+
+        let base = env(&[
+            ("AMENT_PREFIX_PATH", "/opt/ros/jazzy:/ws/install"),
+            ("ROS_DISTRO", "jazzy"),
+        ]);
+        let mut config = Config::default();
+        config.ros1.master_uri = "http://robot:11311".to_string();
+        let noetic = compute_activation("noetic", &ros_root, &base, &config);
+        let back = compute_activation("jazzy", Path::new("/nonexistent"), &noetic, &config);
+        fs::remove_dir_all(&ros_root).unwrap();
+
+        let root = ros_root.display().to_string();
+        assert_eq!(noetic["ROS_VERSION"], "1");
+        assert_eq!(noetic["ROS_MASTER_URI"], "http://robot:11311");
+        assert_eq!(noetic["ROS_ROOT"], format!("{}/share/ros", root));
+        assert_eq!(noetic["ROS_PACKAGE_PATH"], format!("{}/share", root));
+        assert!(!noetic.contains_key("AMENT_PREFIX_PATH"));
+
+        assert_eq!(back["ROS_VERSION"], "2");
+        assert!(!back.contains_key("ROS_MASTER_URI"));
+        assert!(!back.contains_key("ROS_ROOT"));
+    }
+
+    #[test]
+    fn test_compute_activation_keeps_user_master_uri() {
+        let ros_root = Path::new("/nonexistent/opt/ros/noetic");
+        let config = Config::default();
+        let base = env(&[("ROS_MASTER_URI", "http://robot:11311")]);
+
+        let noetic = compute_activation("noetic", ros_root, &base, &config);
+        let jazzy = compute_activation("jazzy", Path::new("/nonexistent"), &noetic, &config);
+        let deactivated = compute_deactivation(&noetic, &config);
+
+        assert_eq!(noetic["ROS_MASTER_URI"], "http://robot:11311");
+        assert_eq!(jazzy["ROS_MASTER_URI"], "http://robot:11311");
+        assert_eq!(deactivated["ROS_MASTER_URI"], "http://robot:11311");
+    }
+
+    #[test]
+    fn test_compute_activation_uses_recorded_python() {
+        let ros_root =
+            std::env::temp_dir().join(format!("rosenv-env-python-{}", std::process::id()));
+        fs::create_dir_all(ros_root.join("conda-meta")).unwrap();
+        fs::create_dir_all(ros_root.join("lib/python3.11/site-packages")).unwrap();
+        fs::create_dir_all(ros_root.join("lib/python3.12/site-packages")).unwrap();
+        fs::write(
+            ros_root.join("conda-meta/python-3.12.7-hc5c86c4_0_cpython.json"),
+            "{}",
+        )
+        .unwrap();
+
+        let activated = compute_activation("jazzy", &ros_root, &EnvMap::new(), &Config::default());
+        fs::remove_dir_all(&ros_root).unwrap();
+
+        assert_eq!(
+            activated["PYTHONPATH"],
+            ros_root
+                .join("lib/python3.12/site-packages")
+                .display()
+                .to_string()
+        );
+        assert_eq!(activated["ROS_PYTHON_VERSION"], "3");
+    }
+
+    #[test]
+    fn test_compute_activation_translates_discovery_settings() {
+        let ros_root = Path::new("/nonexistent/opt/ros");
+        let config = Config::default();
+        let humble = compute_activation(
+            "humble",
+            ros_root,
+            &env(&[
+                ("ROS_DOMAIN_ID", "7"),
+                ("ROS_AUTOMATIC_DISCOVERY_RANGE", "LOCALHOST"),
+                ("ROS_STATIC_PEERS", "192.168.0.2"),
+            ]),
+            &config,
+        );
+        assert_eq!(humble["ROS_DOMAIN_ID"], "7");
+        assert_eq!(humble["ROS_LOCALHOST_ONLY"], "1");
+        assert_eq!(humble["ROS_STATIC_PEERS"], "192.168.0.2");
+
+        let jazzy = compute_activation("jazzy", ros_root, &humble, &config);
+        assert_eq!(jazzy["ROS_AUTOMATIC_DISCOVERY_RANGE"], "LOCALHOST");
+
+        let custom = compute_activation("robot", ros_root, &humble, &config);
+        assert_eq!(custom["ROS_LOCALHOST_ONLY"], "1");
+    }
+
+    #[test]
+    fn test_compute_activation_keeps_discovery_across_generations() {
+        let ros_root = Path::new("/nonexistent/opt/ros");
+        let config = Config::default();
+        let user = [
+            ("ROS_DOMAIN_ID", "7"),
+            ("ROS_AUTOMATIC_DISCOVERY_RANGE", "LOCALHOST"),
+            ("ROS_HOSTNAME", "robot"),
+        ];
+
+        let jazzy = compute_activation("jazzy", ros_root, &env(&user), &config);
+        let noetic = compute_activation("noetic", ros_root, &jazzy, &config);
+        let back = compute_activation("jazzy", ros_root, &noetic, &config);
+
+        for (name, value) in user {
+            assert_eq!(noetic[name], value);
+            assert_eq!(back[name], value);
+        }
+    }
+
+    #[test]
+    fn test_compute_activation_expands_isolated_install() {
+        let ros_root =
+            std::env::temp_dir().join(format!("rosenv-env-isolated-{}", std::process::id()));
+        fs::create_dir_all(&ros_root).unwrap();
+        fs::write(ros_root.join(".colcon_install_layout"), "isolated\n").unwrap();
+        for package in ["rclcpp", "ros_environment"] {
+            fs::create_dir_all(ros_root.join(package).join("share/colcon-core/packages")).unwrap();
+            fs::create_dir_all(ros_root.join(package).join("lib")).unwrap();
+        }
+        fs::create_dir_all(ros_root.join("rclcpp/bin")).unwrap();
+
+        let activated = compute_activation("dev", &ros_root, &EnvMap::new(), &Config::default());
+        fs::remove_dir_all(&ros_root).unwrap();
+
+        let rclcpp = ros_root.join("rclcpp").display().to_string();
+        let ros_environment = ros_root.join("ros_environment").display().to_string();
+        assert_eq!(
+            activated["AMENT_PREFIX_PATH"],
+            format!("{}:{}", rclcpp, ros_environment)
+        );
+        assert_eq!(
+            activated["CMAKE_PREFIX_PATH"],
+            activated["AMENT_PREFIX_PATH"]
+        );
+        assert_eq!(activated["PATH"], format!("{}/bin", rclcpp));
+        assert_eq!(
+            activated["LD_LIBRARY_PATH"],
+            format!("{}/lib:{}/lib", rclcpp, ros_environment)
+        );
+    }
+
+    #[test]
+    fn test_compute_pixi_activation_without_global() {
+        let base = env(&[
+            ("PATH", "/ws/.pixi/envs/default/bin:/opt/ros/humble/bin"),
+            ("CONDA_PREFIX", "/ws/.pixi/envs/default"),
+        ]);
+        let activated = compute_pixi_activation("jazzy", None, &base);
+
+        assert_eq!(activated["PATH"], "/ws/.pixi/envs/default/bin");
+        assert_eq!(activated["ROS_DISTRO"], "jazzy");
+        assert_eq!(activated["CONDA_PREFIX"], "/ws/.pixi/envs/default");
+    }
+
+    #[test]
+    fn test_compute_deactivation() {
+        let base = env(&[
+            ("PATH", "/usr/bin:/opt/ros/jazzy/bin"),
+            ("AMENT_PREFIX_PATH", "/opt/ros/jazzy"),
+            ("PYTHONPATH", "/home/user/lib"),
+            ("ROS_DISTRO", "jazzy"),
+            ("HOME", "/home/user"),
+        ]);
+        let deactivated = compute_deactivation(&base, &Config::default());
+
+        assert_eq!(
+            deactivated,
+            env(&[("PATH", "/usr/bin"), ("HOME", "/home/user")])
+        );
+    }
+
+    #[test]
+    fn test_diff_env() {
+        let before = env(&[
+            ("PATH", "/usr/bin:/bin:/opt/ros/humble/bin"),
+            ("ROS_DISTRO", "humble"),
+            ("GZ_VERSION", "fortress"),
+        ]);
+        let after = env(&[
+            ("PATH", "/bin:/usr/bin:/opt/ros/jazzy/bin"),
+            ("ROS_DISTRO", "jazzy"),
+            ("ROS_VERSION", "2"),
+        ]);
+
+        let diffs = diff_env(&before, &after);
+        assert_eq!(
+            diffs,
+            vec![
+                VarDiff {
+                    name: "GZ_VERSION".to_string(),
+                    change: VarChange::Removed {
+                        old: "fortress".to_string()
+                    },
+                },
+                VarDiff {
+                    name: "PATH".to_string(),
+                    change: VarChange::Path {
+                        added: vec!["/opt/ros/jazzy/bin".to_string()],
+                        removed: vec!["/opt/ros/humble/bin".to_string()],
+                        reordered: true,
+                    },
+                },
+                VarDiff {
+                    name: "ROS_DISTRO".to_string(),
+                    change: VarChange::Changed {
+                        old: "humble".to_string(),
+                        new: "jazzy".to_string()
+                    },
+                },
+                VarDiff {
+                    name: "ROS_VERSION".to_string(),
+                    change: VarChange::Added {
+                        value: "2".to_string()
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clean_env_is_minimal() {
+        let env = clean_env();
+        assert_eq!(env["PATH"], CLEAN_PATH);
+        assert!(env
+            .keys()
+            .all(|k| k == "PATH" || CLEAN_WHITELIST.contains(&k.as_str())));
+    }
+
+    #[test]
+    fn test_managed_vars() {
+        let all = env(&[
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home/user"),
+            ("ROS_DISTRO", "jazzy"),
+        ]);
+        let managed = managed_vars(&all);
+        assert_eq!(managed.len(), 2);
+        assert!(!managed.contains_key("HOME"));
+    }
+
+    #[test]
+    fn test_diff_env_unchanged() {
+        let before = env(&[("PATH", "/usr/bin")]);
+        assert!(diff_env(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{bail, Result};
+
+use crate::config::{EolConfig, EolMode};
+use crate::registry::{lookup, Date, EolStatus};
+
+// Overrides `[eol] mode` for a single run, e.g. ROSENV_EOL=fail in CI
+pub const EOL_ENV_VAR: &str = "ROSENV_EOL";
+
+pub fn parse_mode(value: &str) -> Result<EolMode> {
+    match value {
+        "off" => Ok(EolMode::Off),
+        "warn" => Ok(EolMode::Warn),
+        "fail" => Ok(EolMode::Fail),
+        other => bail!(
+            "Invalid {}='{}'\n\nExpected one of: off, warn, fail",
+            EOL_ENV_VAR,
+            other
+        ),
+    }
+}
+
+pub fn effective_mode(config: &EolConfig) -> Result<EolMode> {
+    match std::env::var(EOL_ENV_VAR) {
+        Ok(value) if !value.is_empty() => parse_mode(&value),
+        _ => Ok(config.mode),
+    }
+}
+
+pub fn eol_message(distro: &str, status: EolStatus) -> Option<String> {
+    match status {
+        EolStatus::Supported => None,
+        EolStatus::EndOfLife(eol) => Some(format!("{} reached EOL on {}", distro, eol)),
+        EolStatus::EndingSoon { eol, days } => Some(format!(
+            "{} reaches EOL on {} (in {} days)",
+            distro, eol, days
+        )),
+    }
+}
+
+pub fn eol_status(distro: &str, config: &EolConfig, today: Date) -> EolStatus {
+    lookup(distro).map_or(EolStatus::Supported, |info| {
+        info.eol_status(today, config.warn_days)
+    })
+}
+
+// The warning to show for a distro; None when supported or checks are off
+pub fn eol_notice(distro: &str, config: &EolConfig) -> Result<Option<String>> {
+    if effective_mode(config)? == EolMode::Off {
+        return Ok(None);
+    }
+    Ok(eol_message(
+        distro,
+        eol_status(distro, config, Date::today()),
+    ))
+}
+
+// Like `eol_notice`, but an error for distros past EOL in fail mode
+pub fn check_eol(distro: &str, config: &EolConfig) -> Result<Option<String>> {
+    let mode = effective_mode(config)?;
+    let status = eol_status(distro, config, Date::today());
+
+    match (mode, status) {
+        (EolMode::Off, _) => Ok(None),
+        (EolMode::Fail, EolStatus::EndOfLife(_)) => bail!(
+            "{}\n\nUpgrade to a supported distribution, or allow it with {}=warn",
+            eol_message(distro, status).unwrap_or_default(),
+            EOL_ENV_VAR
+        ),
+        _ => Ok(eol_message(distro, status)),
+    }
+}
+
+// For commands that run things directly (env, exec, shell): warnings go to
+// stderr, and fail mode stops before anything runs
+pub fn enforce_eol(distro: &str, config: &EolConfig) -> Result<()> {
+    if let Some(notice) = check_eol(distro, config)? {
+        eprintln!("Warning: {}", notice);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eol_message() {
+        let config = EolConfig::default();
+        let today = Date {
+            year: 2026,
+            month: 10,
+            day: 18,
+        };
+
+        assert_eq!(
+            eol_message("iron", eol_status("iron", &config, today)).as_deref(),
+            Some("iron reached EOL on 2024-12-04")
+        );
+        assert_eq!(
+            eol_message("kilted", eol_status("kilted", &config, today)).as_deref(),
+            Some("kilted reaches EOL on 2026-12-31 (in 74 days)")
+        );
+        assert_eq!(eol_status("jazzy", &config, today), EolStatus::Supported);
+        assert_eq!(eol_status("robot", &config, today), EolStatus::Supported);
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(parse_mode("fail").unwrap(), EolMode::Fail);
+        assert!(parse_mode("strict").is_err());
+    }
+}
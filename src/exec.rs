@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{load_config, Config};
+use crate::distro::validate_distro;
+use crate::env::{clean_env, compute_activation, current_env, EnvMap};
+use crate::eol::enforce_eol;
+
+// The environment the command starts from: the caller's, or with --clean only
+// the whitelisted basics
+fn exec_base(clean: bool) -> EnvMap {
+    if clean {
+        clean_env()
+    } else {
+        current_env()
+    }
+}
+
+// `command` is everything after `--`, program first. The child gets the
+// activated environment and nothing else.
+fn exec_command(
+    distro: &str,
+    distro_path: &Path,
+    command: &[String],
+    base: &EnvMap,
+    config: &Config,
+) -> Result<Command> {
+    let Some((program, args)) = command.split_first() else {
+        anyhow::bail!(
+            "No command given\n\nUsage: rosenv exec {} -- <command>",
+            distro
+        );
+    };
+
+    let env = compute_activation(distro, distro_path, base, config);
+
+    let mut child = Command::new(program);
+    child.args(args).env_clear().envs(&env);
+    Ok(child)
+}
+
+pub fn cmd_exec(distro: &str, command: &[String], clean: bool) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+
+    let config = load_config()?;
+    enforce_eol(distro, &config.eol)?;
+    let mut child = exec_command(distro, &distro_path, command, &exec_base(clean), &config)?;
+
+    // exec only returns on failure; on success the command's exit code is ours
+    let err = child.exec();
+
+    Err(err).context(format!("Failed to run '{}'", command[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn command(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn child_env(child: &Command) -> EnvMap {
+        child
+            .get_envs()
+            .filter_map(|(name, value)| {
+                Some((
+                    name.to_string_lossy().to_string(),
+                    value?.to_string_lossy().to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_exec_command_splits_program_and_args() {
+        let child = exec_command(
+            "jazzy",
+            Path::new("/nonexistent/opt/ros/jazzy"),
+            &command(&["ros2", "topic", "echo", "--", "/chatter"]),
+            &EnvMap::new(),
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(child.get_program(), "ros2");
+        let args: Vec<&OsStr> = child.get_args().collect();
+        assert_eq!(args, ["topic", "echo", "--", "/chatter"]);
+    }
+
+    #[test]
+    fn test_exec_command_requires_a_command() {
+        let err = exec_command(
+            "jazzy",
+            Path::new("/nonexistent/opt/ros/jazzy"),
+            &[],
+            &EnvMap::new(),
+            &Config::default(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("rosenv exec jazzy -- <command>"));
+    }
+
+    #[test]
+    fn test_exec_command_activates_on_top_of_base() {
+        let base: EnvMap = [
+            ("HOME", "/home/user"),
+            ("PATH", "/usr/bin:/opt/ros/humble/bin"),
+            ("ROS_DISTRO", "humble"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let child = exec_command(
+            "jazzy",
+            Path::new("/nonexistent/opt/ros/jazzy"),
+            &command(&["true"]),
+            &base,
+            &Config::default(),
+        )
+        .unwrap();
+        let env = child_env(&child);
+
+        assert_eq!(env["HOME"], "/home/user");
+        assert_eq!(env["PATH"], "/usr/bin");
+        assert_eq!(env["ROS_DISTRO"], "jazzy");
+    }
+}
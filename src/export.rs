@@ -0,0 +1,177 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+use crate::config::load_config;
+use crate::distro::validate_distro;
+use crate::env::{
+    clean_env, compute_activation, current_env, is_managed_var, managed_vars, EnvMap,
+};
+use crate::eol::enforce_eol;
+use crate::overlay::{apply_overlay, check_overlay_distro};
+use crate::quote::quote_posix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// KEY="value" lines for .env files
+    Dotenv,
+    /// A flat JSON object
+    Json,
+    /// Environment= lines for systemd units
+    Systemd,
+    /// KEY=value lines for `docker run --env-file`
+    Docker,
+    /// export statements for POSIX shells
+    Shell,
+}
+
+fn quote_dotenv(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn quote_systemd(assignment: &str) -> String {
+    let escaped = assignment
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+// `removed` lists variables the environment no longer has; only the shell format
+// can express that, the others describe a fresh process environment
+pub fn render(vars: &EnvMap, removed: &[String], format: ExportFormat) -> Result<String> {
+    if format == ExportFormat::Json {
+        return Ok(format!("{}\n", serde_json::to_string_pretty(vars)?));
+    }
+
+    let mut output = String::new();
+    for (name, value) in vars {
+        let line = match format {
+            ExportFormat::Dotenv => format!("{}={}", name, quote_dotenv(value)),
+            ExportFormat::Systemd => {
+                format!(
+                    "Environment={}",
+                    quote_systemd(&format!("{}={}", name, value))
+                )
+            }
+            ExportFormat::Docker => {
+                if value.contains('\n') {
+                    anyhow::bail!(
+                        "{} contains a newline, which --env-file cannot represent",
+                        name
+                    );
+                }
+                format!("{}={}", name, value)
+            }
+            ExportFormat::Shell => format!("export {}={}", name, quote_posix(value)),
+            ExportFormat::Json => unreachable!(),
+        };
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    if format == ExportFormat::Shell {
+        for name in removed {
+            output.push_str(&format!("unset {}\n", name));
+        }
+    }
+
+    Ok(output)
+}
+
+pub fn cmd_env(
+    distro: &str,
+    format: ExportFormat,
+    clean: bool,
+    overlays: &[PathBuf],
+) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+
+    let config = load_config()?;
+    enforce_eol(distro, &config.eol)?;
+    let base = if clean { clean_env() } else { current_env() };
+    let mut activated = compute_activation(distro, &distro_path, &base, &config);
+    for overlay in overlays {
+        apply_overlay(&mut activated, overlay)?;
+        check_overlay_distro(overlay, distro, config.overlays.on_mismatch)?;
+    }
+
+    let removed: Vec<String> = base
+        .keys()
+        .filter(|name| is_managed_var(name) && !activated.contains_key(*name))
+        .cloned()
+        .collect();
+
+    print!("{}", render(&managed_vars(&activated), &removed, format)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> EnvMap {
+        [
+            ("AMENT_PREFIX_PATH", "/opt/ros/jazzy"),
+            ("ROS_DISTRO", "it's \"100%\" $HOME"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_render_dotenv() {
+        let output = render(&vars(), &[], ExportFormat::Dotenv).unwrap();
+        assert_eq!(
+            output,
+            "AMENT_PREFIX_PATH=\"/opt/ros/jazzy\"\nROS_DISTRO=\"it's \\\"100%\\\" \\$HOME\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_systemd() {
+        let output = render(&vars(), &[], ExportFormat::Systemd).unwrap();
+        assert!(output.contains("Environment=\"AMENT_PREFIX_PATH=/opt/ros/jazzy\"\n"));
+        assert!(output.contains("Environment=\"ROS_DISTRO=it's \\\"100%%\\\" $HOME\"\n"));
+    }
+
+    #[test]
+    fn test_render_docker() {
+        let output = render(&vars(), &[], ExportFormat::Docker).unwrap();
+        assert!(output.contains("AMENT_PREFIX_PATH=/opt/ros/jazzy\n"));
+
+        let mut bad = vars();
+        bad.insert("ROS_DISTRO".to_string(), "a\nb".to_string());
+        assert!(render(&bad, &[], ExportFormat::Docker).is_err());
+    }
+
+    #[test]
+    fn test_render_shell() {
+        let output = render(&vars(), &[], ExportFormat::Shell).unwrap();
+        assert!(output.contains("export ROS_DISTRO='it'\\''s \"100%\" $HOME'\n"));
+    }
+
+    #[test]
+    fn test_render_removed_vars() {
+        let removed = vec!["GZ_VERSION".to_string()];
+
+        let output = render(&vars(), &removed, ExportFormat::Shell).unwrap();
+        assert!(output.ends_with("unset GZ_VERSION\n"));
+
+        let output = render(&vars(), &removed, ExportFormat::Dotenv).unwrap();
+        assert!(!output.contains("GZ_VERSION"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let output = render(&vars(), &[], ExportFormat::Json).unwrap();
+        let parsed: EnvMap = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed, vars());
+    }
+}
@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use crate::conda::read_records;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GazeboRelease {
+    Citadel,
+    Edifice,
+    Fortress,
+    Garden,
+    Harmonic,
+    Ionic,
+    Jetty,
+}
+
+impl GazeboRelease {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GazeboRelease::Citadel => "citadel",
+            GazeboRelease::Edifice => "edifice",
+            GazeboRelease::Fortress => "fortress",
+            GazeboRelease::Garden => "garden",
+            GazeboRelease::Harmonic => "harmonic",
+            GazeboRelease::Ionic => "ionic",
+            GazeboRelease::Jetty => "jetty",
+        }
+    }
+
+    // conda-forge packages that ship the simulator for each release
+    fn packages(&self) -> &'static [&'static str] {
+        match self {
+            GazeboRelease::Citadel => &["libignition-gazebo3", "ignition-gazebo3"],
+            GazeboRelease::Edifice => &["libignition-gazebo5", "ignition-gazebo5"],
+            GazeboRelease::Fortress => &["libignition-gazebo6", "ignition-gazebo6"],
+            GazeboRelease::Garden => &["libgz-sim7", "gz-sim7"],
+            GazeboRelease::Harmonic => &["libgz-sim8", "gz-sim8"],
+            GazeboRelease::Ionic => &["libgz-sim9", "gz-sim9"],
+            GazeboRelease::Jetty => &["libgz-sim10", "gz-sim10"],
+        }
+    }
+
+    // Releases up to Fortress are still Ignition and read IGN_* instead of GZ_*
+    fn is_ignition(&self) -> bool {
+        matches!(
+            self,
+            GazeboRelease::Citadel | GazeboRelease::Edifice | GazeboRelease::Fortress
+        )
+    }
+
+    pub fn resource_path_var(&self) -> &'static str {
+        if self.is_ignition() {
+            "IGN_GAZEBO_RESOURCE_PATH"
+        } else {
+            "GZ_SIM_RESOURCE_PATH"
+        }
+    }
+
+    pub fn system_plugin_path_var(&self) -> &'static str {
+        if self.is_ignition() {
+            "IGN_GAZEBO_SYSTEM_PLUGIN_PATH"
+        } else {
+            "GZ_SIM_SYSTEM_PLUGIN_PATH"
+        }
+    }
+
+    pub fn gui_plugin_path_var(&self) -> &'static str {
+        if self.is_ignition() {
+            "IGN_GUI_PLUGIN_PATH"
+        } else {
+            "GZ_GUI_PLUGIN_PATH"
+        }
+    }
+}
+
+const RELEASES: [GazeboRelease; 7] = [
+    GazeboRelease::Citadel,
+    GazeboRelease::Edifice,
+    GazeboRelease::Fortress,
+    GazeboRelease::Garden,
+    GazeboRelease::Harmonic,
+    GazeboRelease::Ionic,
+    GazeboRelease::Jetty,
+];
+
+pub fn detect_gazebo(prefix: &Path) -> Option<(GazeboRelease, String)> {
+    let records = read_records(prefix);
+
+    for release in RELEASES {
+        if let Some(record) = records
+            .iter()
+            .find(|r| release.packages().contains(&r.name.as_str()))
+        {
+            return Some((release, record.version.clone()));
+        }
+    }
+
+    None
+}
+
+// Gazebo Classic, which ROS 1 and early ROS 2 pair with, named like the
+// registry does: `gazebo11` for conda-forge's `gazebo-11.14.0`. It needs none
+// of the variables above, so only `info` looks for it.
+pub fn detect_gazebo_classic(prefix: &Path) -> Option<(String, String)> {
+    let record = read_records(prefix)
+        .into_iter()
+        .find(|r| r.name == "gazebo")?;
+    let major = record.version.split('.').next()?;
+    Some((format!("gazebo{}", major), record.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_gazebo_harmonic() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-gz-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(conda_meta.join("libgz-sim8-8.6.0-h1234567_2.json"), "{}").unwrap();
+        fs::write(conda_meta.join("ros-jazzy-ros-gz-1.0.0-py311_0.json"), "{}").unwrap();
+
+        let detected = detect_gazebo(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(
+            detected,
+            Some((GazeboRelease::Harmonic, "8.6.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_detect_gazebo_paired_releases() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-gz-paired-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(
+            conda_meta.join("libignition-gazebo3-3.15.1-h1_0.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(conda_meta.join("gazebo-11.14.0-h1_5.json"), "{}").unwrap();
+
+        let detected = detect_gazebo(&prefix);
+        let classic = detect_gazebo_classic(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(
+            detected,
+            Some((GazeboRelease::Citadel, "3.15.1".to_string()))
+        );
+        assert_eq!(
+            classic,
+            Some(("gazebo11".to_string(), "11.14.0".to_string()))
+        );
+        assert_eq!(
+            GazeboRelease::Citadel.gui_plugin_path_var(),
+            "IGN_GUI_PLUGIN_PATH"
+        );
+        assert_eq!(
+            GazeboRelease::Jetty.gui_plugin_path_var(),
+            "GZ_GUI_PLUGIN_PATH"
+        );
+    }
+
+    #[test]
+    fn test_detect_gazebo_none() {
+        assert_eq!(detect_gazebo(Path::new("/nonexistent/prefix")), None);
+    }
+
+    #[test]
+    fn test_fortress_uses_ignition_vars() {
+        assert_eq!(
+            GazeboRelease::Fortress.resource_path_var(),
+            "IGN_GAZEBO_RESOURCE_PATH"
+        );
+        assert_eq!(
+            GazeboRelease::Harmonic.resource_path_var(),
+            "GZ_SIM_RESOURCE_PATH"
+        );
+    }
+}
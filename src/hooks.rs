@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::quote::quote_posix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    Activate,
+    Deactivate,
+}
+
+impl HookKind {
+    fn dir_name(&self) -> &'static str {
+        match self {
+            HookKind::Activate => "activate.d",
+            HookKind::Deactivate => "deactivate.d",
+        }
+    }
+}
+
+fn hook_extensions(shell: &str) -> &'static [&'static str] {
+    match shell {
+        "zsh" => &["sh", "zsh"],
+        "bash" => &["sh", "bash"],
+        _ => &["sh"],
+    }
+}
+
+// Hooks in `all` run before distro-specific ones; within a directory they run
+// in file name order so users can prefix them with numbers.
+pub fn find_hooks(hooks_dir: &Path, distro: &str, kind: HookKind, shell: &str) -> Vec<PathBuf> {
+    let extensions = hook_extensions(shell);
+    let mut hooks = Vec::new();
+
+    for scope in ["all", distro] {
+        let dir = hooks_dir.join(scope).join(kind.dir_name());
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        let mut scoped: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.extension()
+                    .map(|ext| extensions.contains(&ext.to_string_lossy().as_ref()))
+                    .unwrap_or(false)
+            })
+            .collect();
+        scoped.sort();
+        hooks.extend(scoped);
+    }
+
+    hooks
+}
+
+pub fn render_hooks(hooks: &[PathBuf], kind: HookKind) -> String {
+    if hooks.is_empty() {
+        return String::new();
+    }
+
+    let mut script = format!("# rosenv {} hooks\n", kind.dir_name());
+    for hook in hooks {
+        let path = quote_posix(&hook.display().to_string());
+        script.push_str(&format!(". {}\n", path));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_hooks_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rosenv-hooks-{}-{}", name, std::process::id()));
+        for (scope, file) in [
+            ("all", "10-log-dir.sh"),
+            ("all", "20-prompt.zsh"),
+            ("all", "30-greeting.fish"),
+            ("jazzy", "00-cyclonedds.sh"),
+            ("humble", "00-license.sh"),
+        ] {
+            let hook_dir = dir.join(scope).join("activate.d");
+            fs::create_dir_all(&hook_dir).unwrap();
+            fs::write(hook_dir.join(file), "true\n").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_find_hooks_orders_all_before_distro() {
+        let dir = make_hooks_dir("order");
+        let hooks = find_hooks(&dir, "jazzy", HookKind::Activate, "bash");
+        let names: Vec<String> = hooks
+            .iter()
+            .map(|h| h.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names, vec!["10-log-dir.sh", "00-cyclonedds.sh"]);
+    }
+
+    #[test]
+    fn test_find_hooks_respects_shell_extensions() {
+        let dir = make_hooks_dir("ext");
+        let zsh = find_hooks(&dir, "humble", HookKind::Activate, "zsh");
+        let bash = find_hooks(&dir, "humble", HookKind::Activate, "bash");
+        let deactivate = find_hooks(&dir, "humble", HookKind::Deactivate, "zsh");
+        fs::remove_dir_all(&dir).unwrap();
+
+        // The .fish hook never matches: only POSIX shells evaluate the output
+        assert_eq!(zsh.len(), 3);
+        assert_eq!(bash.len(), 2);
+        assert!(deactivate.is_empty());
+    }
+
+    #[test]
+    fn test_render_hooks() {
+        assert_eq!(render_hooks(&[], HookKind::Activate), "");
+
+        let script = render_hooks(
+            &[PathBuf::from(
+                "/home/user/.config/rosenv/hooks/all/deactivate.d/a.sh",
+            )],
+            HookKind::Deactivate,
+        );
+        assert!(script.contains("deactivate.d hooks"));
+        assert!(script.contains(". '/home/user/.config/rosenv/hooks/all/deactivate.d/a.sh'"));
+    }
+}
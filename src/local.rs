@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::distro::validate_distro_name;
+
+// Checked in this order in every directory while walking up from the cwd
+pub const LOCAL_FILES: [&str; 2] = [".ros-distro", ".rosenv.toml"];
+
+#[derive(Debug, Deserialize)]
+struct LocalConfig {
+    distro: String,
+}
+
+pub fn find_local_file(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        for name in LOCAL_FILES {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+pub fn read_local_distro(file: &Path) -> Result<String> {
+    let content = fs::read_to_string(file).context(format!("Failed to read {}", file.display()))?;
+
+    let distro = if file.extension().is_some_and(|ext| ext == "toml") {
+        let config: LocalConfig =
+            toml::from_str(&content).context(format!("Failed to parse {}", file.display()))?;
+        config.distro
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    if distro.is_empty() {
+        anyhow::bail!("No distribution set in {}", file.display());
+    }
+    // The shell hook passes this straight to `rosenv activate` on every cd
+    validate_distro_name(&distro).context(format!("Invalid distribution in {}", file.display()))?;
+
+    Ok(distro)
+}
+
+pub fn cmd_local(show_path: bool) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let Some(file) = find_local_file(&cwd) else {
+        anyhow::bail!(
+            "No .ros-distro or .rosenv.toml found in {} or its parents",
+            cwd.display()
+        );
+    };
+
+    if show_path {
+        println!("{}", file.display());
+    } else {
+        println!("{}", read_local_distro(&file)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rosenv-local-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("src/pkg")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_find_local_file_walks_up() {
+        let dir = project_dir("walk");
+        fs::write(dir.join(".ros-distro"), "jazzy\n").unwrap();
+
+        let found = find_local_file(&dir.join("src/pkg"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found, Some(dir.join(".ros-distro")));
+    }
+
+    #[test]
+    fn test_read_ros_distro_file() {
+        let dir = project_dir("plain");
+        let file = dir.join(".ros-distro");
+        fs::write(&file, "# pinned for the robot\n\n  humble  \n").unwrap();
+
+        let distro = read_local_distro(&file);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(distro.unwrap(), "humble");
+    }
+
+    #[test]
+    fn test_read_rosenv_toml() {
+        let dir = project_dir("toml");
+        let file = dir.join(".rosenv.toml");
+        fs::write(&file, "distro = \"jazzy\"\n").unwrap();
+
+        let distro = read_local_distro(&file);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(distro.unwrap(), "jazzy");
+    }
+
+    #[test]
+    fn test_read_empty_file_fails() {
+        let dir = project_dir("empty");
+        let file = dir.join(".ros-distro");
+        fs::write(&file, "\n# nothing here\n").unwrap();
+
+        let distro = read_local_distro(&file);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(distro.is_err());
+    }
+
+    #[test]
+    fn test_read_invalid_name_fails() {
+        let dir = project_dir("invalid");
+        let file = dir.join(".ros-distro");
+        fs::write(&file, "jazzy; rm -rf ~\n").unwrap();
+
+        let distro = read_local_distro(&file);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(distro.is_err());
+    }
+}
@@ -1,14 +1,34 @@
+mod ament;
 mod cli;
+mod colcon;
+mod conda;
+mod config;
+mod diff;
 mod distro;
 mod doctor;
+mod env;
+mod eol;
+mod exec;
+mod export;
+mod gazebo;
+mod hooks;
+mod local;
+mod overlay;
 mod pixi;
+mod pixi_global;
+mod prompt;
+mod python;
+mod quote;
+mod registry;
 mod setup;
 mod shell;
+mod sources;
+mod subshell;
 mod symlink;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands, PixiCommands};
+use cli::{Cli, ColconDefaultsCommands, Commands, PixiCommands};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -17,25 +37,66 @@ fn main() -> Result<()> {
         Commands::Setup => setup::cmd_setup(),
         Commands::List { names_only, short } => shell::cmd_list(names_only, short),
         Commands::Status => shell::cmd_status(),
-        Commands::Activate { distro } => {
-            let sh = std::env::var("SHELL")
-                .ok()
-                .and_then(|s| s.split('/').next_back().map(String::from))
-                .unwrap_or_else(|| "bash".to_string());
-
-            let script = shell::generate_activation_script(&distro, &sh)?;
+        Commands::Activate {
+            distro,
+            explain: true,
+            json,
+            shell: sh,
+        } => shell::cmd_explain(&distro, &shell::resolve_shell(sh), json),
+        Commands::Activate {
+            distro, shell: sh, ..
+        } => {
+            let script = shell::generate_activation_script(&distro, &shell::resolve_shell(sh))?;
             print!("{}", script);
             Ok(())
         }
-        Commands::Deactivate => {
-            print!("{}", shell::generate_deactivation_script());
+        Commands::Deactivate { shell: sh } => {
+            let distro = distro::get_current_distro();
+            let script =
+                shell::generate_deactivation_script(distro.as_deref(), &shell::resolve_shell(sh))?;
+            print!("{}", script);
             Ok(())
         }
-        Commands::Info { distro } => shell::cmd_info(&distro),
-        Commands::Init { shell: sh } => {
+        Commands::Env {
+            distro,
+            format,
+            clean,
+            overlay,
+        } => export::cmd_env(&distro, format, clean, &overlay),
+        Commands::DirenvStdlib => {
+            print!("{}", shell::generate_direnv_stdlib());
+            Ok(())
+        }
+        Commands::Exec {
+            distro,
+            clean,
+            command,
+        } => exec::cmd_exec(&distro, &command, clean),
+        Commands::Shell { distro } => subshell::cmd_shell(&distro),
+        Commands::Local { path } => local::cmd_local(path),
+        Commands::Info {
+            distro,
+            packages: true,
+            ros_only,
+            grep,
+        } => shell::cmd_info_packages(&distro, ros_only, grep.as_deref()),
+        Commands::Info { distro, .. } => shell::cmd_info(&distro),
+        Commands::Pkgs {
+            distro,
+            grep,
+            build_type,
+            json,
+        } => ament::cmd_pkgs(&distro, grep.as_deref(), build_type.as_deref(), json),
+        Commands::FindPkg { package } => ament::cmd_find_pkg(&package),
+        Commands::Diff { a, b, json } => diff::cmd_diff(&a, &b, json),
+        Commands::Init { shell: sh, prompt } => {
             println!("{}", shell::generate_shell_integration(&sh));
+            if prompt {
+                println!("{}", shell::generate_prompt_segment());
+            }
             Ok(())
         }
+        Commands::Prompt { format } => prompt::cmd_prompt(&format),
         Commands::Remove { distro } => symlink::remove_symlink(&distro),
         Commands::Cleanup => symlink::cmd_cleanup(),
         Commands::Refresh => symlink::cmd_refresh(),
@@ -43,5 +104,8 @@ fn main() -> Result<()> {
         Commands::Pixi { command } => match command {
             PixiCommands::Activate => pixi::cmd_pixi_activate(),
         },
+        Commands::ColconDefaults { command } => match command {
+            ColconDefaultsCommands::Edit { distro } => colcon::cmd_edit(&distro),
+        },
     }
 }
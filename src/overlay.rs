@@ -0,0 +1,265 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::conda::mutex_distro;
+use crate::config::MismatchPolicy;
+use crate::distro::get_ros_root;
+use crate::env::{prepend_path, site_packages_dirs, EnvMap};
+
+// colcon records whether an install space is "merged" or "isolated" here
+const INSTALL_LAYOUT_FILE: &str = ".colcon_install_layout";
+
+pub fn is_colcon_install(path: &Path) -> bool {
+    path.join(INSTALL_LAYOUT_FILE).is_file() || path.join("setup.sh").is_file()
+}
+
+// Every prefix of an install space: the space itself for merged installs, one
+// per package for isolated ones
+pub fn overlay_prefixes(install: &Path) -> Vec<PathBuf> {
+    let layout = fs::read_to_string(install.join(INSTALL_LAYOUT_FILE)).unwrap_or_default();
+    if layout.trim() != "isolated" {
+        return vec![install.to_path_buf()];
+    }
+
+    let mut prefixes = Vec::new();
+    if let Ok(entries) = fs::read_dir(install) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.join("share/colcon-core/packages").is_dir() {
+                prefixes.push(path);
+            }
+        }
+    }
+    prefixes.sort();
+    prefixes
+}
+
+const SETUP_FILES: [&str; 6] = [
+    "setup.sh",
+    "setup.bash",
+    "setup.zsh",
+    "local_setup.sh",
+    "local_setup.bash",
+    "local_setup.zsh",
+];
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+// What the setup files hardcode at build time: the underlay prefixes colcon
+// chains via COLCON_CURRENT_PREFIX, and any ROS_DISTRO they export
+fn scan_setup_files(install: &Path, prefixes: &mut Vec<PathBuf>, distros: &mut BTreeSet<String>) {
+    for file in SETUP_FILES {
+        let Ok(content) = fs::read_to_string(install.join(file)) else {
+            continue;
+        };
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches("export ");
+            if let Some(value) = line.strip_prefix("COLCON_CURRENT_PREFIX=") {
+                let value = unquote(value);
+                // Skip the install space locating itself at runtime
+                if value.starts_with('/') && !value.contains('$') {
+                    prefixes.push(PathBuf::from(value));
+                }
+            } else if let Some(value) = line.strip_prefix("ROS_DISTRO=") {
+                let value = unquote(value);
+                if !value.is_empty() && !value.contains('$') {
+                    distros.insert(value.to_string());
+                }
+            }
+        }
+    }
+}
+
+// ament_cmake records the AMENT_PREFIX_PATH each package was built with
+fn scan_parent_prefixes(install: &Path, prefixes: &mut Vec<PathBuf>) {
+    for prefix in overlay_prefixes(install) {
+        let index = prefix.join("share/ament_index/resource_index/parent_prefix_path");
+        let Ok(entries) = fs::read_dir(index) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let content = fs::read_to_string(entry.path()).unwrap_or_default();
+            prefixes.extend(
+                content
+                    .trim()
+                    .split(':')
+                    .filter(|p| !p.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+    }
+}
+
+// /opt/ros/<distro> by name, anything else by its RoboStack mutex package
+fn prefix_distro(prefix: &Path) -> Option<String> {
+    if let Ok(rest) = prefix.strip_prefix(get_ros_root()) {
+        return rest
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().to_string());
+    }
+    mutex_distro(prefix)
+}
+
+// Distributions an install space was built against
+pub fn built_against(install: &Path) -> BTreeSet<String> {
+    let mut prefixes = Vec::new();
+    let mut distros = BTreeSet::new();
+
+    scan_setup_files(install, &mut prefixes, &mut distros);
+    scan_parent_prefixes(install, &mut prefixes);
+
+    distros.extend(prefixes.iter().filter_map(|p| prefix_distro(p)));
+    distros
+}
+
+pub fn check_overlay_distro(install: &Path, distro: &str, policy: MismatchPolicy) -> Result<()> {
+    if policy == MismatchPolicy::Ignore {
+        return Ok(());
+    }
+
+    let built = built_against(install);
+    if built.is_empty() || built.contains(distro) {
+        return Ok(());
+    }
+
+    let built: Vec<&str> = built.iter().map(String::as_str).collect();
+    let message = format!(
+        "'{}' was built against {}, but {} is active",
+        install.display(),
+        built.join(", "),
+        distro
+    );
+
+    match policy {
+        MismatchPolicy::Refuse => anyhow::bail!(
+            "{}\n\nRebuild it: rm -rf build install log && colcon build\n\
+             Or set [overlays] on_mismatch = \"warn\" in ~/.config/rosenv/config.toml",
+            message
+        ),
+        _ => eprintln!("Warning: {}; expect ABI errors, rebuild it", message),
+    }
+
+    Ok(())
+}
+
+pub fn apply_overlay(env: &mut EnvMap, install: &Path) -> Result<()> {
+    if !is_colcon_install(install) {
+        anyhow::bail!(
+            "'{}' is not a colcon install directory\n\nBuild it first: colcon build",
+            install.display()
+        );
+    }
+
+    let install = fs::canonicalize(install)?;
+
+    for prefix in overlay_prefixes(&install) {
+        prepend_path(env, "AMENT_PREFIX_PATH", &prefix);
+        prepend_path(env, "CMAKE_PREFIX_PATH", &prefix);
+        prepend_path(env, "PATH", &prefix.join("bin"));
+        for site_packages in site_packages_dirs(&prefix) {
+            prepend_path(env, "PYTHONPATH", &site_packages);
+        }
+    }
+    prepend_path(env, "COLCON_PREFIX_PATH", &install);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install_dir(name: &str, layout: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("rosenv-overlay-{}-{}", name, std::process::id()))
+            .join("install");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(INSTALL_LAYOUT_FILE), format!("{}\n", layout)).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_overlay_prefixes_isolated() {
+        let install = install_dir("isolated", "isolated");
+        for pkg in ["talker", "listener"] {
+            fs::create_dir_all(install.join(pkg).join("share/colcon-core/packages")).unwrap();
+        }
+        fs::create_dir_all(install.join("_local")).unwrap();
+
+        let prefixes = overlay_prefixes(&install);
+        fs::remove_dir_all(install.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            prefixes,
+            vec![install.join("listener"), install.join("talker")]
+        );
+    }
+
+    #[test]
+    fn test_apply_overlay_merged() {
+        let install = install_dir("merged", "merged");
+        fs::create_dir_all(install.join("bin")).unwrap();
+
+        let mut env = EnvMap::new();
+        env.insert(
+            "AMENT_PREFIX_PATH".to_string(),
+            "/opt/ros/jazzy".to_string(),
+        );
+        env.insert("PATH".to_string(), "/usr/bin".to_string());
+        let result = apply_overlay(&mut env, &install);
+        let install = fs::canonicalize(&install).unwrap();
+        fs::remove_dir_all(install.parent().unwrap()).unwrap();
+
+        result.unwrap();
+        let root = install.display().to_string();
+        assert_eq!(env["AMENT_PREFIX_PATH"], format!("{}:/opt/ros/jazzy", root));
+        assert_eq!(env["PATH"], format!("{}/bin:/usr/bin", root));
+        assert_eq!(env["COLCON_PREFIX_PATH"], root);
+    }
+
+    #[test]
+    fn test_built_against_setup_and_ament_index() {
+        let install = install_dir("built", "merged");
+        fs::write(
+            install.join("setup.sh"),
+            "COLCON_CURRENT_PREFIX=\"/opt/ros/humble\"\n\
+             COLCON_CURRENT_PREFIX=\"$_colcon_prefix_chain_sh_COLCON_CURRENT_PREFIX\"\n",
+        )
+        .unwrap();
+        let index = install.join("share/ament_index/resource_index/parent_prefix_path");
+        fs::create_dir_all(&index).unwrap();
+        fs::write(index.join("talker"), "/opt/ros/iron:/usr\n").unwrap();
+
+        let built = built_against(&install);
+        let jazzy = check_overlay_distro(&install, "jazzy", MismatchPolicy::Refuse);
+        let humble = check_overlay_distro(&install, "humble", MismatchPolicy::Refuse);
+        fs::remove_dir_all(install.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            built.into_iter().collect::<Vec<_>>(),
+            vec!["humble".to_string(), "iron".to_string()]
+        );
+        assert!(jazzy.is_err());
+        assert!(humble.is_ok());
+    }
+
+    #[test]
+    fn test_check_overlay_distro_unknown_origin() {
+        let install = install_dir("unknown", "merged");
+        let result = check_overlay_distro(&install, "jazzy", MismatchPolicy::Refuse);
+        fs::remove_dir_all(install.parent().unwrap()).unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_apply_overlay_rejects_non_install() {
+        let mut env = EnvMap::new();
+        assert!(apply_overlay(&mut env, Path::new("/nonexistent/install")).is_err());
+    }
+}
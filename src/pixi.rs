@@ -1,64 +1,25 @@
 use anyhow::Result;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::distro::get_ros_root;
-
-const HELPERS: &str = include_str!("assets/helpers.sh");
-const PIXI_BASE: &str = include_str!("assets/pixi_activate_base.sh");
-const PIXI_GLOBAL: &str = include_str!("assets/pixi_activate_global.sh");
+use crate::config::load_config;
+use crate::distro::{detect_env_distro, get_ros_root};
+use crate::env::{compute_pixi_activation, current_env};
+use crate::overlay::{check_overlay_distro, is_colcon_install};
+use crate::shell::render_env_changes;
 
 pub fn detect_pixi_ros_distro() -> Option<String> {
-    let pixi_env = PathBuf::from(".pixi/envs/default/conda-meta");
-    if !pixi_env.exists() {
+    let prefix = Path::new(".pixi/envs/default");
+    if !prefix.join("conda-meta").exists() {
         return None;
     }
 
-    let distros = ["humble", "jazzy", "iron", "rolling"];
-
-    for distro in &distros {
-        if let Ok(entries) = fs::read_dir(&pixi_env) {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if name.starts_with("ros2-distro-mutex") && name.contains(distro) {
-                    return Some(distro.to_string());
-                }
-            }
-        }
-    }
-
-    for distro in &distros {
-        if let Ok(entries) = fs::read_dir(&pixi_env) {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if name.starts_with(&format!("ros-{}-", distro)) {
-                    return Some(distro.to_string());
-                }
-            }
-        }
-    }
-
-    None
+    detect_env_distro(prefix, "default")
 }
 
-fn generate_pixi_activate_script(distro: &str, append_global: bool) -> String {
-    let ros_root = format!("/opt/ros/{}", distro);
-
-    let mut script = String::new();
-    script.push_str(HELPERS);
-    script.push('\n');
-    script.push_str(PIXI_BASE);
-
-    if append_global {
-        script.push('\n');
-        script.push_str(PIXI_GLOBAL);
-    }
-
-    script.push_str("\nunset -f _rosenv_strip _rosenv_append\n");
-
-    script
-        .replace("{distro}", distro)
-        .replace("{ros_root}", &ros_root)
+fn generate_pixi_activate_script(distro: &str, global_root: Option<&Path>) -> String {
+    let before = current_env();
+    let after = compute_pixi_activation(distro, global_root, &before);
+    render_env_changes(&before, &after)
 }
 
 pub fn cmd_pixi_activate() -> Result<()> {
@@ -73,12 +34,12 @@ pub fn cmd_pixi_activate() -> Result<()> {
                 script.push_str(&format!(
                     "# rosenv: pixi has ROS {distro}, appending global /opt/ros/{distro}\n"
                 ));
-                script.push_str(&generate_pixi_activate_script(distro, true));
+                script.push_str(&generate_pixi_activate_script(distro, Some(&global_path)));
             } else {
                 script.push_str(&format!(
                     "# rosenv: pixi has ROS {distro}, no matching global found\n"
                 ));
-                script.push_str(&generate_pixi_activate_script(distro, false));
+                script.push_str(&generate_pixi_activate_script(distro, None));
             }
         }
         None => {
@@ -86,6 +47,13 @@ pub fn cmd_pixi_activate() -> Result<()> {
         }
     }
 
+    if let Some(distro) = pixi_distro.as_deref() {
+        let policy = load_config()?.overlays.on_mismatch;
+        if is_colcon_install(Path::new("install")) {
+            check_overlay_distro(Path::new("install"), distro, policy)?;
+        }
+    }
+
     if PathBuf::from("install/setup.bash").exists() {
         script.push_str("source install/setup.bash\n");
         script.push_str("unset LD_LIBRARY_PATH\n");
@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::distro::get_pixi_home;
+
+// `pixi global` declares every global env in this manifest
+#[derive(Debug, Default, Deserialize)]
+pub struct GlobalManifest {
+    #[serde(default)]
+    pub envs: BTreeMap<String, GlobalEnv>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GlobalEnv {
+    pub channels: Vec<toml::Value>,
+    pub dependencies: BTreeMap<String, toml::Value>,
+    pub exposed: BTreeMap<String, String>,
+    pub platform: Option<String>,
+}
+
+// Channels and specs are either plain strings or tables with extra fields
+fn value_to_string(value: &toml::Value, key: &str) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Table(table) => match table.get(key) {
+            Some(toml::Value::String(s)) => s.clone(),
+            _ => value.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+impl GlobalEnv {
+    pub fn channel_names(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|c| value_to_string(c, "channel"))
+            .collect()
+    }
+
+    pub fn dependency_specs(&self) -> Vec<(String, String)> {
+        self.dependencies
+            .iter()
+            .map(|(name, spec)| (name.clone(), value_to_string(spec, "version")))
+            .collect()
+    }
+
+    pub fn is_ros(&self) -> bool {
+        self.dependencies
+            .keys()
+            .any(|name| name.starts_with("ros-") || name.starts_with("ros2-"))
+    }
+}
+
+pub fn get_global_manifest_path() -> PathBuf {
+    get_pixi_home().join("manifests").join("pixi-global.toml")
+}
+
+pub fn parse_global_manifest(content: &str) -> Result<GlobalManifest> {
+    Ok(toml::from_str(content)?)
+}
+
+// None when pixi has never written a manifest (pixi global before 0.33)
+pub fn load_global_manifest() -> Result<Option<GlobalManifest>> {
+    let path = get_global_manifest_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let manifest =
+        parse_global_manifest(&content).context(format!("Failed to parse {}", path.display()))?;
+    Ok(Some(manifest))
+}
+
+// ROS envs the manifest declares but that have not been installed into `envs_dir`
+pub fn missing_ros_envs(manifest: &GlobalManifest, envs_dir: &Path) -> Vec<String> {
+    manifest
+        .envs
+        .iter()
+        .filter(|(name, env)| env.is_ros() && !envs_dir.join(name).is_dir())
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+version = 1
+
+[envs.ros-jazzy-desktop]
+channels = ["robostack-staging", "conda-forge"]
+dependencies = { ros-jazzy-desktop = "*", colcon-common-extensions = { version = ">=0.3" } }
+exposed = { ros2 = "ros2", rviz2 = "rviz2" }
+
+[envs.ros-humble]
+channels = ["robostack-staging", "conda-forge"]
+dependencies = { ros-humble-ros-base = "*" }
+
+[envs.ripgrep]
+channels = ["conda-forge"]
+dependencies = { ripgrep = "*" }
+"#;
+
+    #[test]
+    fn test_parse_global_manifest() {
+        let manifest = parse_global_manifest(MANIFEST).unwrap();
+        let jazzy = &manifest.envs["ros-jazzy-desktop"];
+
+        assert_eq!(
+            jazzy.channel_names(),
+            vec!["robostack-staging", "conda-forge"]
+        );
+        assert_eq!(
+            jazzy.dependency_specs(),
+            vec![
+                ("colcon-common-extensions".to_string(), ">=0.3".to_string()),
+                ("ros-jazzy-desktop".to_string(), "*".to_string()),
+            ]
+        );
+        assert_eq!(jazzy.exposed.len(), 2);
+        assert!(!manifest.envs["ripgrep"].is_ros());
+    }
+
+    #[test]
+    fn test_missing_ros_envs() {
+        let envs_dir =
+            std::env::temp_dir().join(format!("rosenv-global-envs-{}", std::process::id()));
+        fs::create_dir_all(envs_dir.join("ros-jazzy-desktop")).unwrap();
+
+        let manifest = parse_global_manifest(MANIFEST).unwrap();
+        let missing = missing_ros_envs(&manifest, &envs_dir);
+        fs::remove_dir_all(&envs_dir).unwrap();
+
+        assert_eq!(missing, vec!["ros-humble"]);
+    }
+}
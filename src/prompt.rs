@@ -0,0 +1,132 @@
+use anyhow::Result;
+
+use crate::distro::{detect_variant, get_ros_root};
+use crate::env::{current_env, EnvMap};
+
+pub const DEFAULT_FORMAT: &str = "{distro}{overlay?}{flags?}";
+
+// Everything here runs on every prompt, so fields are only computed when the
+// format asks for them and none of them spawn processes
+fn field_value(field: &str, distro: &str, env: &EnvMap) -> Result<String> {
+    let value = match field {
+        "distro" => distro.to_string(),
+        "variant" => detect_variant(&get_ros_root().join(distro), distro).unwrap_or_default(),
+        "profile" => env
+            .get("PIXI_ENVIRONMENT_NAME")
+            .cloned()
+            .unwrap_or_default(),
+        "overlay" => {
+            let count = env.get("COLCON_PREFIX_PATH").map_or(0, |value| {
+                value.split(':').filter(|e| !e.is_empty()).count()
+            });
+            if count > 0 {
+                count.to_string()
+            } else {
+                String::new()
+            }
+        }
+        "flags" => {
+            let mut flags = String::new();
+            // /opt/ros/<distro> missing or a dangling symlink
+            if !get_ros_root().join(distro).exists() {
+                flags.push('!');
+            }
+            flags
+        }
+        _ => anyhow::bail!(
+            "Unknown prompt field '{{{}}}'\n\nAvailable: distro, variant, profile, overlay, flags",
+            field
+        ),
+    };
+    Ok(value)
+}
+
+// `{field?}` renders a short decorated form only when the field has a value
+fn decorate(field: &str, value: &str) -> String {
+    match field {
+        "variant" => format!("/{}", value),
+        "profile" => format!("@{}", value),
+        "overlay" => format!("+{}", value),
+        "flags" => format!(" {}", value),
+        _ => value.to_string(),
+    }
+}
+
+pub fn render_prompt(format: &str, distro: &str, env: &EnvMap) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = format;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            anyhow::bail!("Unclosed '{{' in prompt format '{}'", format);
+        };
+
+        let spec = &rest[start + 1..start + len];
+        let (field, optional) = match spec.strip_suffix('?') {
+            Some(field) => (field, true),
+            None => (spec, false),
+        };
+
+        let value = field_value(field, distro, env)?;
+        if optional {
+            if !value.is_empty() {
+                output.push_str(&decorate(field, &value));
+            }
+        } else {
+            output.push_str(&value);
+        }
+
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+pub fn cmd_prompt(format: &str) -> Result<()> {
+    let env = current_env();
+    let Some(distro) = env.get("ROS_DISTRO") else {
+        return Ok(());
+    };
+
+    print!("{}", render_prompt(format, distro, &env)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prompt_plain_fields() {
+        let prompt = render_prompt("ros:{distro}", "jazzy", &EnvMap::new()).unwrap();
+        assert_eq!(prompt, "ros:jazzy");
+    }
+
+    #[test]
+    fn test_render_prompt_optional_fields() {
+        let env = EnvMap::from([(
+            "COLCON_PREFIX_PATH".to_string(),
+            "/ws/a/install:/ws/b/install".to_string(),
+        )]);
+        let with_overlays = render_prompt("{distro}{overlay?}", "jazzy", &env).unwrap();
+        let without_overlays =
+            render_prompt("{distro}{overlay?}", "jazzy", &EnvMap::new()).unwrap();
+
+        assert_eq!(with_overlays, "jazzy+2");
+        assert_eq!(without_overlays, "jazzy");
+    }
+
+    #[test]
+    fn test_render_prompt_flags_missing_distro() {
+        let prompt = render_prompt("{distro}{flags?}", "no-such-distro", &EnvMap::new()).unwrap();
+        assert_eq!(prompt, "no-such-distro !");
+    }
+
+    #[test]
+    fn test_render_prompt_rejects_bad_formats() {
+        assert!(render_prompt("{nope}", "jazzy", &EnvMap::new()).is_err());
+        assert!(render_prompt("{distro", "jazzy", &EnvMap::new()).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use crate::conda::read_records;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub full: String,
+}
+
+impl PythonVersion {
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+
+        Some(PythonVersion {
+            major,
+            minor,
+            full: version.to_string(),
+        })
+    }
+
+    pub fn site_packages(&self, prefix: &Path) -> PathBuf {
+        prefix
+            .join("lib")
+            .join(format!("python{}.{}", self.major, self.minor))
+            .join("site-packages")
+    }
+}
+
+// The interpreter the env was solved with, from its `python-*.json` record
+pub fn detect_python(prefix: &Path) -> Option<PythonVersion> {
+    read_records(prefix)
+        .into_iter()
+        .find(|record| record.name == "python")
+        .and_then(|record| PythonVersion::parse(&record.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_python() {
+        let prefix = std::env::temp_dir().join(format!("rosenv-python-{}", std::process::id()));
+        let conda_meta = prefix.join("conda-meta");
+        fs::create_dir_all(&conda_meta).unwrap();
+        fs::write(
+            conda_meta.join("python-3.12.7-hc5c86c4_0_cpython.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(conda_meta.join("python_abi-3.12-5_cp312.json"), "{}").unwrap();
+
+        let detected = detect_python(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+
+        let python = detected.unwrap();
+        assert_eq!((python.major, python.minor), (3, 12));
+        assert_eq!(python.full, "3.12.7");
+        assert_eq!(
+            python.site_packages(Path::new("/env")),
+            Path::new("/env/lib/python3.12/site-packages")
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert_eq!(PythonVersion::parse("3"), None);
+        assert_eq!(PythonVersion::parse("three.eleven"), None);
+    }
+}
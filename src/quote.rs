@@ -0,0 +1,21 @@
+// Quoting for values spliced into generated shell code. Everything that is
+// not a literal part of a template goes through here.
+
+pub fn quote_posix(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_posix() {
+        assert_eq!(quote_posix("/opt/ros/jazzy"), "'/opt/ros/jazzy'");
+        assert_eq!(quote_posix("it's"), "'it'\\''s'");
+        assert_eq!(
+            quote_posix("$(rm -rf ~) `id` \"x\""),
+            "'$(rm -rf ~) `id` \"x\"'"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+const fn date(year: u16, month: u8, day: u8) -> Date {
+    Date { year, month, day }
+}
+
+impl Date {
+    // Days since 1970-01-01 (Howard Hinnant's days_from_civil)
+    fn days_since_epoch(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    fn from_days_since_epoch(days: i64) -> Date {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        date(year as u16, month as u8, day as u8)
+    }
+
+    pub fn today() -> Date {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Date::from_days_since_epoch((seconds / 86400) as i64)
+    }
+
+    pub fn days_until(&self, other: Date) -> i64 {
+        other.days_since_epoch() - self.days_since_epoch()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// How nodes find each other, which decides the variables worth setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryStyle {
+    // ROS 1: everything goes through the master
+    RosMaster,
+    // ROS 2 up to Iron: ROS_DOMAIN_ID plus ROS_LOCALHOST_ONLY
+    LocalhostOnly,
+    // ROS 2 from Jazzy: ROS_DOMAIN_ID plus ROS_AUTOMATIC_DISCOVERY_RANGE
+    DiscoveryRange,
+}
+
+impl DiscoveryStyle {
+    pub fn variables(&self) -> &'static [&'static str] {
+        match self {
+            DiscoveryStyle::RosMaster => &["ROS_MASTER_URI", "ROS_HOSTNAME", "ROS_IP"],
+            DiscoveryStyle::LocalhostOnly => &["ROS_DOMAIN_ID", "ROS_LOCALHOST_ONLY"],
+            DiscoveryStyle::DiscoveryRange => &[
+                "ROS_DOMAIN_ID",
+                "ROS_AUTOMATIC_DISCOVERY_RANGE",
+                "ROS_STATIC_PEERS",
+            ],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DistroInfo {
+    pub name: &'static str,
+    pub ros_version: u8,
+    pub release: Date,
+    // None for rolling, which never reaches end of life
+    pub eol: Option<Date>,
+    pub lts: bool,
+    // ROS 1 has no middleware abstraction
+    pub default_rmw: Option<&'static str>,
+    pub discovery: DiscoveryStyle,
+    pub gazebo: Option<&'static str>,
+}
+
+const fn ros1(
+    name: &'static str,
+    release: Date,
+    eol: Date,
+    lts: bool,
+    gazebo: &'static str,
+) -> DistroInfo {
+    DistroInfo {
+        name,
+        ros_version: 1,
+        release,
+        eol: Some(eol),
+        lts,
+        default_rmw: None,
+        discovery: DiscoveryStyle::RosMaster,
+        gazebo: Some(gazebo),
+    }
+}
+
+const fn ros2(
+    name: &'static str,
+    release: Date,
+    eol: Date,
+    lts: bool,
+    rmw: &'static str,
+    discovery: DiscoveryStyle,
+    gazebo: &'static str,
+) -> DistroInfo {
+    DistroInfo {
+        name,
+        ros_version: 2,
+        release,
+        eol: Some(eol),
+        lts,
+        default_rmw: Some(rmw),
+        discovery,
+        gazebo: Some(gazebo),
+    }
+}
+
+const FASTRTPS: &str = "rmw_fastrtps_cpp";
+const CYCLONEDDS: &str = "rmw_cyclonedds_cpp";
+const LOCALHOST_ONLY: DiscoveryStyle = DiscoveryStyle::LocalhostOnly;
+const DISCOVERY_RANGE: DiscoveryStyle = DiscoveryStyle::DiscoveryRange;
+
+// Release and end-of-life dates from REP 3 and REP 2000. Releases older than
+// Indigo predate every package manager rosenv supports.
+pub const DISTROS: &[DistroInfo] = &[
+    ros1(
+        "indigo",
+        date(2014, 7, 22),
+        date(2019, 4, 30),
+        true,
+        "gazebo2",
+    ),
+    ros1(
+        "jade",
+        date(2015, 5, 23),
+        date(2017, 5, 31),
+        false,
+        "gazebo5",
+    ),
+    ros1(
+        "kinetic",
+        date(2016, 5, 23),
+        date(2021, 4, 30),
+        true,
+        "gazebo7",
+    ),
+    ros1(
+        "lunar",
+        date(2017, 5, 23),
+        date(2019, 5, 31),
+        false,
+        "gazebo7",
+    ),
+    ros1(
+        "melodic",
+        date(2018, 5, 23),
+        date(2023, 6, 27),
+        true,
+        "gazebo9",
+    ),
+    ros1(
+        "noetic",
+        date(2020, 5, 23),
+        date(2025, 5, 31),
+        true,
+        "gazebo11",
+    ),
+    ros2(
+        "ardent",
+        date(2017, 12, 8),
+        date(2018, 12, 31),
+        false,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "gazebo9",
+    ),
+    ros2(
+        "bouncy",
+        date(2018, 7, 2),
+        date(2019, 7, 31),
+        false,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "gazebo9",
+    ),
+    ros2(
+        "crystal",
+        date(2018, 12, 14),
+        date(2019, 12, 31),
+        false,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "gazebo9",
+    ),
+    ros2(
+        "dashing",
+        date(2019, 5, 31),
+        date(2021, 5, 31),
+        true,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "gazebo9",
+    ),
+    ros2(
+        "eloquent",
+        date(2019, 11, 22),
+        date(2020, 11, 30),
+        false,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "gazebo9",
+    ),
+    ros2(
+        "foxy",
+        date(2020, 6, 5),
+        date(2023, 6, 20),
+        true,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "citadel",
+    ),
+    ros2(
+        "galactic",
+        date(2021, 5, 23),
+        date(2022, 12, 9),
+        false,
+        CYCLONEDDS,
+        LOCALHOST_ONLY,
+        "edifice",
+    ),
+    ros2(
+        "humble",
+        date(2022, 5, 23),
+        date(2027, 5, 31),
+        true,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "fortress",
+    ),
+    ros2(
+        "iron",
+        date(2023, 5, 23),
+        date(2024, 12, 4),
+        false,
+        FASTRTPS,
+        LOCALHOST_ONLY,
+        "fortress",
+    ),
+    ros2(
+        "jazzy",
+        date(2024, 5, 23),
+        date(2029, 5, 31),
+        true,
+        FASTRTPS,
+        DISCOVERY_RANGE,
+        "harmonic",
+    ),
+    ros2(
+        "kilted",
+        date(2025, 5, 23),
+        date(2026, 12, 31),
+        false,
+        FASTRTPS,
+        DISCOVERY_RANGE,
+        "ionic",
+    ),
+    ros2(
+        "lyrical",
+        date(2026, 5, 22),
+        date(2031, 5, 31),
+        true,
+        FASTRTPS,
+        DISCOVERY_RANGE,
+        "jetty",
+    ),
+    DistroInfo {
+        name: "rolling",
+        ros_version: 2,
+        release: date(2020, 6, 5),
+        eol: None,
+        lts: false,
+        default_rmw: Some(FASTRTPS),
+        discovery: DiscoveryStyle::DiscoveryRange,
+        gazebo: None,
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EolStatus {
+    Supported,
+    EndingSoon { eol: Date, days: i64 },
+    EndOfLife(Date),
+}
+
+impl DistroInfo {
+    pub fn eol_status(&self, today: Date, warn_days: u32) -> EolStatus {
+        match self.eol {
+            Some(eol) if eol < today => EolStatus::EndOfLife(eol),
+            Some(eol) if today.days_until(eol) <= i64::from(warn_days) => EolStatus::EndingSoon {
+                eol,
+                days: today.days_until(eol),
+            },
+            _ => EolStatus::Supported,
+        }
+    }
+}
+
+pub fn lookup(distro: &str) -> Option<&'static DistroInfo> {
+    DISTROS.iter().find(|info| info.name == distro)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let jazzy = lookup("jazzy").unwrap();
+        assert_eq!(jazzy.ros_version, 2);
+        assert!(jazzy.lts);
+        assert_eq!(jazzy.discovery, DiscoveryStyle::DiscoveryRange);
+        assert_eq!(jazzy.gazebo, Some("harmonic"));
+
+        assert_eq!(
+            lookup("humble").unwrap().discovery,
+            DiscoveryStyle::LocalhostOnly
+        );
+        assert_eq!(lookup("noetic").unwrap().ros_version, 1);
+        assert!(lookup("rolling").unwrap().eol.is_none());
+        assert!(lookup("robot").is_none());
+    }
+
+    #[test]
+    fn test_registry_is_consistent() {
+        for info in DISTROS {
+            if let Some(eol) = info.eol {
+                assert!(info.release < eol, "{} ends before it starts", info.name);
+            }
+            assert_eq!(info.default_rmw.is_some(), info.ros_version == 2);
+        }
+    }
+
+    #[test]
+    fn test_date_arithmetic() {
+        assert_eq!(date(1970, 1, 1).days_since_epoch(), 0);
+        assert_eq!(date(2024, 12, 4).days_since_epoch(), 20061);
+        assert_eq!(Date::from_days_since_epoch(20061), date(2024, 12, 4));
+        assert_eq!(date(2024, 2, 28).days_until(date(2024, 3, 1)), 2);
+    }
+
+    #[test]
+    fn test_eol_status() {
+        let iron = lookup("iron").unwrap();
+        assert_eq!(
+            iron.eol_status(date(2026, 10, 18), 90),
+            EolStatus::EndOfLife(date(2024, 12, 4))
+        );
+        assert_eq!(
+            iron.eol_status(date(2024, 11, 4), 90),
+            EolStatus::EndingSoon {
+                eol: date(2024, 12, 4),
+                days: 30
+            }
+        );
+        assert_eq!(iron.eol_status(date(2023, 6, 1), 90), EolStatus::Supported);
+        assert_eq!(
+            lookup("rolling").unwrap().eol_status(date(2099, 1, 1), 90),
+            EolStatus::Supported
+        );
+    }
+
+    #[test]
+    fn test_date_display() {
+        assert_eq!(date(2027, 5, 31).to_string(), "2027-05-31");
+    }
+}
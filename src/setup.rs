@@ -1,15 +1,18 @@
 use anyhow::Result;
 
-use crate::distro::scan_pixi_ros_installations;
+use crate::config::load_config;
+use crate::distro::{get_pixi_envs_dir, SourceKind};
+use crate::pixi_global::{get_global_manifest_path, load_global_manifest, missing_ros_envs};
+use crate::sources::discover_all;
 use crate::symlink::{check_opt_ros_writable, create_symlink};
 
 pub fn cmd_setup() -> Result<()> {
-    println!("Scanning ~/.pixi/envs for ROS 2 installations...\n");
+    println!("Scanning pixi global envs, conda envs and source builds for ROS installations...\n");
 
-    let distros = scan_pixi_ros_installations()?;
+    let distros = discover_all(&load_config()?.discovery)?;
 
     if distros.is_empty() {
-        println!("No ROS distributions found in ~/.pixi/envs/\n");
+        println!("No ROS distributions found\n");
         println!("Install with pixi global:");
         println!("  pixi global install --environment ros-humble -c robostack-staging ros-humble-desktop");
         println!(
@@ -19,12 +22,38 @@ pub fn cmd_setup() -> Result<()> {
         return Ok(());
     }
 
+    let manifest_path = get_global_manifest_path();
+    let has_manifest = manifest_path.exists();
+
     println!("Found distributions:");
     for distro in &distros {
-        println!("  • ros-{}-*  → {}", distro.name, distro.path.display());
+        let undeclared = distro.kind == SourceKind::PixiGlobal && distro.declared.is_none();
+        let note = if has_manifest && undeclared {
+            "  (not in pixi-global.toml)"
+        } else {
+            ""
+        };
+        println!(
+            "  • {} ({})  → {}{}",
+            distro.name,
+            distro.kind.name(),
+            distro.path.display(),
+            note
+        );
     }
     println!();
 
+    if let Ok(Some(manifest)) = load_global_manifest() {
+        let missing = missing_ros_envs(&manifest, &get_pixi_envs_dir());
+        if !missing.is_empty() {
+            println!("Declared in {} but not installed:", manifest_path.display());
+            for name in &missing {
+                println!("  • {}", name);
+            }
+            println!("  Install them: pixi global sync\n");
+        }
+    }
+
     println!("Checking /opt/ros permissions...");
     check_opt_ros_writable()?;
     println!("✓ /opt/ros is writable\n");
@@ -1,32 +1,217 @@
 use anyhow::Result;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::conda::read_packages;
+use crate::config::{get_hooks_dir, load_config, Config};
+use crate::distro::{
+    detect_ros_version, get_current_distro, get_pixi_envs_dir, get_ros_root, list_distributions,
+    validate_distro,
+};
+use crate::env::{
+    compute_activation, compute_deactivation, current_env, diff_env, managed_var_names, EnvMap,
+    VarChange, SHARED_VARS,
+};
+use crate::eol::{check_eol, eol_notice};
+use crate::gazebo::{detect_gazebo, detect_gazebo_classic};
+use crate::hooks::{find_hooks, render_hooks, HookKind};
+use crate::pixi_global::{get_global_manifest_path, load_global_manifest};
+use crate::python::detect_python;
+use crate::quote::quote_posix;
+use crate::registry::lookup;
+use crate::sources::{classify, installed_distributions};
 
-use crate::distro::{get_current_distro, get_ros_root, list_distributions, validate_distro};
-
-const HELPERS: &str = include_str!("assets/helpers.sh");
-const ACTIVATE_TEMPLATE: &str = include_str!("assets/activate.sh");
-const DEACTIVATE: &str = include_str!("assets/deactivate.sh");
 const INIT_TEMPLATE: &str = include_str!("assets/init.sh");
+const DIRENV_STDLIB: &str = include_str!("assets/direnv.sh");
+const PROMPT_SEGMENT: &str = include_str!("assets/prompt.sh");
+
+pub fn current_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .and_then(|s| s.split('/').next_back().map(String::from))
+        .unwrap_or_else(|| "bash".to_string())
+}
+
+// The wrappers from `rosenv init` pass --shell, since $SHELL is the login
+// shell and not necessarily the one evaluating the output
+pub fn resolve_shell(flag: Option<String>) -> String {
+    flag.unwrap_or_else(current_shell)
+}
+
+fn export_statement(name: &str, value: &str) -> String {
+    format!("export {}={}\n", name, quote_posix(value))
+}
+
+fn unset_statement(name: &str) -> String {
+    format!("unset {}\n", name)
+}
+
+// Plain assignments that turn `before` into `after`. All path manipulation
+// happens here, so evaluating the result spawns no subprocesses.
+pub fn render_env_changes(before: &EnvMap, after: &EnvMap) -> String {
+    let mut script = String::new();
+    for diff in diff_env(before, after) {
+        match after.get(&diff.name) {
+            Some(value) => script.push_str(&export_statement(&diff.name, value)),
+            None => script.push_str(&unset_statement(&diff.name)),
+        }
+    }
+    script
+}
 
-pub fn generate_activation_script(distro: &str, _shell: &str) -> Result<String> {
+pub fn generate_activation_script(distro: &str, shell: &str) -> Result<String> {
     let distro_path = validate_distro(distro)?;
-    let ros_root = distro_path.display().to_string();
+    let config = load_config()?;
+    let hooks = find_hooks(&get_hooks_dir(), distro, HookKind::Activate, shell);
+    let notice = check_eol(distro, &config.eol)?;
+
+    let mut script =
+        render_activation_script(distro, &distro_path, &current_env(), &config, &hooks);
+    // The wrapper evals everything activate prints, so warn from the script
+    if let Some(notice) = notice {
+        script.push_str(&warning_statement(&notice));
+    }
+    Ok(script)
+}
+
+fn warning_statement(message: &str) -> String {
+    format!(
+        "printf '%s\\n' {} >&2\n",
+        quote_posix(&format!("Warning: {}", message))
+    )
+}
 
-    let script = format!("{}\n{}", HELPERS, ACTIVATE_TEMPLATE)
-        .replace("{distro}", distro)
-        .replace("{ros_root}", &ros_root);
+pub fn render_activation_script(
+    distro: &str,
+    distro_path: &Path,
+    base: &EnvMap,
+    config: &Config,
+    hooks: &[PathBuf],
+) -> String {
+    let after = compute_activation(distro, distro_path, base, config);
+
+    let mut script = format!(
+        "# rosenv: activate ROS {} {}\n",
+        detect_ros_version(distro_path, distro),
+        distro
+    );
+    script.push_str(&render_env_changes(base, &after));
+    script.push_str(&render_hooks(hooks, HookKind::Activate));
+    script
+}
 
+pub fn cmd_explain(distro: &str, shell: &str, json: bool) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+
+    let before = current_env();
+    let after = compute_activation(distro, &distro_path, &before, &load_config()?);
+    let diffs = diff_env(&before, &after);
+    let hooks = find_hooks(&get_hooks_dir(), distro, HookKind::Activate, shell);
+
+    if json {
+        let output = serde_json::json!({
+            "distro": distro,
+            "path": distro_path,
+            "changes": diffs,
+            "hooks": hooks,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if diffs.is_empty() {
+        println!("Activating {} would not change the environment", distro);
+    } else {
+        println!("Activating {} would change:\n", distro);
+    }
+
+    for diff in &diffs {
+        match &diff.change {
+            VarChange::Added { value } => println!("  + {}={}", diff.name, value),
+            VarChange::Removed { old } => println!("  - {} (was {})", diff.name, old),
+            VarChange::Changed { old, new } => {
+                println!("  ~ {}: {} → {}", diff.name, old, new)
+            }
+            VarChange::Path {
+                added,
+                removed,
+                reordered,
+            } => {
+                println!("  ~ {}", diff.name);
+                for entry in added {
+                    println!("      + {}", entry);
+                }
+                for entry in removed {
+                    println!("      - {}", entry);
+                }
+                if *reordered {
+                    println!("      ↕ entries reordered");
+                }
+            }
+        }
+    }
+
+    if !hooks.is_empty() {
+        println!("\nHooks sourced afterwards (not evaluated):");
+        for hook in &hooks {
+            println!("  {}", hook.display());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn generate_deactivation_script(distro: Option<&str>, shell: &str) -> Result<String> {
+    let config = load_config()?;
+    let mut script = String::new();
+
+    // Deactivation hooks run first so they still see the active environment
+    if let Some(distro) = distro {
+        let hooks = find_hooks(&get_hooks_dir(), distro, HookKind::Deactivate, shell);
+        script.push_str(&render_hooks(&hooks, HookKind::Deactivate));
+    }
+
+    script.push_str(&render_deactivation(&current_env(), &config));
     Ok(script)
 }
 
-pub fn generate_deactivation_script() -> String {
-    DEACTIVATE.to_string()
+fn render_deactivation(before: &EnvMap, config: &Config) -> String {
+    let mut script = String::new();
+    let after = compute_deactivation(before, config);
+    // Even when nothing but /opt/ros entries were left, the shell must not
+    // keep the old PATH
+    let path = after.get("PATH").map_or("", String::as_str);
+    script.push_str(&export_statement("PATH", path));
+
+    // Unset unconditionally so a half-activated shell is cleaned up too
+    for var in managed_var_names().filter(|var| !SHARED_VARS.contains(var)) {
+        script.push_str(&unset_statement(var));
+    }
+
+    for var in SHARED_VARS.iter().filter(|var| **var != "PATH") {
+        if before.get(*var) != after.get(*var) {
+            match after.get(*var) {
+                Some(value) => script.push_str(&export_statement(var, value)),
+                None => script.push_str(&unset_statement(var)),
+            }
+        }
+    }
+
+    script
 }
 
 pub fn generate_shell_integration(shell: &str) -> String {
     INIT_TEMPLATE.replace("{shell}", shell)
 }
 
+pub fn generate_prompt_segment() -> String {
+    PROMPT_SEGMENT.to_string()
+}
+
+pub fn generate_direnv_stdlib() -> String {
+    DIRENV_STDLIB.to_string()
+}
+
 pub fn cmd_list(names_only: bool, short: bool) -> Result<()> {
     let distros = list_distributions()?;
 
@@ -47,12 +232,36 @@ pub fn cmd_list(names_only: bool, short: bool) -> Result<()> {
     } else {
         println!("Available ROS distributions:");
         let current = get_current_distro();
+        let width = distros.iter().map(String::len).max().unwrap_or(0);
+        let eol_config = load_config()?.eol;
+        let installed = installed_distributions()?;
+        let mut notices = Vec::new();
+        for distro in &installed {
+            notices.extend(eol_notice(&distro.name, &eol_config)?);
+        }
 
-        for distro in distros {
-            if Some(&distro) == current.as_ref() {
-                println!("  * {} (active)", distro);
-            } else {
-                println!("    {}", distro);
+        for distro in installed {
+            let active = Some(&distro.name) == current.as_ref();
+            let release = match lookup(&distro.name) {
+                Some(info) if info.lts => format!("ROS {} LTS", info.ros_version),
+                Some(info) => format!("ROS {}", info.ros_version),
+                None => "custom".to_string(),
+            };
+            let line = format!(
+                "  {} {:<width$}  {:<12}  {:<9}{}",
+                if active { "*" } else { " " },
+                distro.name,
+                distro.kind.name(),
+                release,
+                if active { "  (active)" } else { "" },
+            );
+            println!("{}", line.trim_end());
+        }
+
+        if !notices.is_empty() {
+            println!();
+            for notice in notices {
+                println!("⚠ {}", notice);
             }
         }
     }
@@ -63,17 +272,31 @@ pub fn cmd_list(names_only: bool, short: bool) -> Result<()> {
 pub fn cmd_status() -> Result<()> {
     match get_current_distro() {
         Some(distro) => {
-            println!("ROS 2 {} is active\n", distro);
+            let ros_version = std::env::var("ROS_VERSION").unwrap_or_else(|_| "2".to_string());
+            println!("ROS {} {} is active", ros_version, distro);
+            if let Some(notice) = eol_notice(&distro, &load_config()?.eol)? {
+                println!("⚠ {}", notice);
+            }
+            println!();
 
             println!("Environment:");
             if let Ok(ros_version) = std::env::var("ROS_VERSION") {
                 println!("  ROS_VERSION:       {}", ros_version);
             }
             println!("  ROS_DISTRO:        {}", distro);
+            if let Some(python) = detect_python(&get_ros_root().join(&distro)) {
+                println!("  Python:            {}", python.full);
+            }
 
             if let Ok(ament) = std::env::var("AMENT_PREFIX_PATH") {
                 println!("  AMENT_PREFIX_PATH: {}", ament);
             }
+            if let Ok(package_path) = std::env::var("ROS_PACKAGE_PATH") {
+                println!("  ROS_PACKAGE_PATH:  {}", package_path);
+            }
+            if let Ok(master_uri) = std::env::var("ROS_MASTER_URI") {
+                println!("  ROS_MASTER_URI:    {}", master_uri);
+            }
 
             let setup_path = get_ros_root().join(&distro).join("setup.zsh");
             if setup_path.exists() {
@@ -82,7 +305,7 @@ pub fn cmd_status() -> Result<()> {
             }
         }
         None => {
-            println!("No ROS 2 distribution active\n");
+            println!("No ROS distribution active\n");
 
             let distros = list_distributions()?;
             if !distros.is_empty() {
@@ -100,19 +323,120 @@ pub fn cmd_status() -> Result<()> {
     Ok(())
 }
 
+// The `pixi global` env behind a distribution, if its target lives in the
+// pixi envs directory
+pub fn pixi_global_env_name(distro_path: &Path) -> Option<String> {
+    let target = fs::canonicalize(distro_path).ok()?;
+    let envs_dir = fs::canonicalize(get_pixi_envs_dir()).ok()?;
+    if target.parent()? != envs_dir {
+        return None;
+    }
+    Some(target.file_name()?.to_string_lossy().to_string())
+}
+
+fn print_global_declaration(distro_path: &Path) {
+    let Some(env_name) = pixi_global_env_name(distro_path) else {
+        return;
+    };
+
+    // A broken manifest is only worth a warning, as in setup
+    let manifest = match load_global_manifest() {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => {
+            println!("Pixi global:  {}", env_name);
+            return;
+        }
+        Err(e) => {
+            println!("Pixi global:  {} (⚠ {})", env_name, e);
+            return;
+        }
+    };
+
+    let Some(env) = manifest.envs.get(&env_name) else {
+        println!(
+            "Pixi global:  {} (⚠ not declared in {})",
+            env_name,
+            get_global_manifest_path().display()
+        );
+        return;
+    };
+
+    println!("Pixi global:  {}", env_name);
+    println!("  Channels:     {}", env.channel_names().join(", "));
+    let dependencies: Vec<String> = env
+        .dependency_specs()
+        .iter()
+        .map(|(name, spec)| format!("{} {}", name, spec))
+        .collect();
+    println!("  Dependencies: {}", dependencies.join(", "));
+    if !env.exposed.is_empty() {
+        let exposed: Vec<&str> = env.exposed.keys().map(String::as_str).collect();
+        println!("  Exposed:      {}", exposed.join(", "));
+    }
+    if let Some(platform) = &env.platform {
+        println!("  Platform:     {}", platform);
+    }
+}
+
 pub fn cmd_info(distro: &str) -> Result<()> {
     let distro_path = validate_distro(distro)?;
 
     println!("Distribution: {}", distro);
     println!("Path:         {}", distro_path.display());
+    println!("ROS version:  {}", detect_ros_version(&distro_path, distro));
+
+    let info = lookup(distro);
+    match info {
+        Some(info) => {
+            let lts = if info.lts { " (LTS)" } else { "" };
+            println!("Released:     {}{}", info.release, lts);
+            match info.eol {
+                Some(eol) => println!("End of life:  {}", eol),
+                None => println!("End of life:  none (rolling release)"),
+            }
+            if let Some(rmw) = info.default_rmw {
+                println!("Default RMW:  {}", rmw);
+            }
+            println!("Discovery:    {}", info.discovery.variables().join(", "));
+        }
+        None => println!("Registry:     not a known ROS distribution"),
+    }
 
+    println!("Source:       {}", classify(&distro_path, distro).name());
     if distro_path.is_symlink() {
-        println!("Type:         Symlink");
         if let Ok(target) = fs::read_link(&distro_path) {
             println!("Target:       {}", target.display());
         }
-    } else {
-        println!("Type:         Directory");
+    }
+
+    print_global_declaration(&distro_path);
+
+    match detect_python(&distro_path) {
+        Some(python) => println!("Python:       {}", python.full),
+        None => println!("Python:       unknown"),
+    }
+
+    // Gazebo is only found through conda-meta; apt installs it outside /opt/ros
+    let paired = info.and_then(|info| info.gazebo);
+    let detected = detect_gazebo(&distro_path)
+        .map(|(gazebo, version)| (gazebo.name().to_string(), version))
+        .or_else(|| detect_gazebo_classic(&distro_path));
+    let searchable = distro_path.join("conda-meta").is_dir();
+    match (detected, paired) {
+        (Some((gazebo, version)), Some(paired)) if gazebo != paired => println!(
+            "Gazebo:       {} ({}), {} pairs with {}",
+            gazebo, version, distro, paired
+        ),
+        (Some((gazebo, version)), _) => println!("Gazebo:       {} ({})", gazebo, version),
+        (None, Some(paired)) if !searchable => {
+            println!(
+                "Gazebo:       pairs with {} (only detected in conda envs)",
+                paired
+            )
+        }
+        (None, Some(paired)) => println!("Gazebo:       not installed (pairs with {})", paired),
+        (None, None) if !searchable => println!("Gazebo:       unknown"),
+        (None, None) => println!("Gazebo:       not installed"),
     }
 
     println!("\nSetup files:");
@@ -141,13 +465,59 @@ pub fn cmd_info(distro: &str) -> Result<()> {
     Ok(())
 }
 
+fn is_ros_package(name: &str) -> bool {
+    name.starts_with("ros-") || name.starts_with("ros2-")
+}
+
+pub fn cmd_info_packages(distro: &str, ros_only: bool, grep: Option<&str>) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+
+    let all = read_packages(&distro_path);
+    if all.is_empty() {
+        anyhow::bail!(
+            "No conda-meta records in {}\n\nPackage listing needs a pixi or conda environment",
+            distro_path.display()
+        );
+    }
+
+    let grep = grep.map(str::to_lowercase);
+    let packages: Vec<_> = all
+        .iter()
+        .filter(|p| !ros_only || is_ros_package(&p.name))
+        .filter(|p| {
+            grep.as_ref()
+                .is_none_or(|g| p.name.to_lowercase().contains(g))
+        })
+        .collect();
+
+    let name_width = packages.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let version_width = packages.iter().map(|p| p.version.len()).max().unwrap_or(0);
+    let build_width = packages.iter().map(|p| p.build.len()).max().unwrap_or(0);
+
+    for package in &packages {
+        println!(
+            "{:<nw$}  {:<vw$}  {:<bw$}  {}",
+            package.name,
+            package.version,
+            package.build,
+            package.channel,
+            nw = name_width,
+            vw = version_width,
+            bw = build_width,
+        );
+    }
+
+    println!("\n{} of {} packages", packages.len(), all.len());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_generate_deactivation_script() {
-        let script = generate_deactivation_script();
+        let script = generate_deactivation_script(None, "bash").unwrap();
 
         assert!(script.contains("unset ROS_DISTRO"));
         assert!(script.contains("unset ROS_VERSION"));
@@ -156,6 +526,203 @@ mod tests {
         assert!(script.contains("PATH="));
     }
 
+    fn hostile_prefix(name: &str) -> PathBuf {
+        let prefix = std::env::temp_dir()
+            .join(format!("rosenv-quote-{}-{}", name, std::process::id()))
+            .join(
+                "pixi home/it's \"$HOME\" $(touch pwned-subst) `touch pwned-tick`;touch pwned-semi",
+            );
+        fs::create_dir_all(prefix.join("bin")).unwrap();
+        fs::create_dir_all(prefix.join("lib/python3.12/site-packages")).unwrap();
+        prefix
+    }
+
+    // Runs the script in bash from an empty directory and returns the printed
+    // variables plus whether any injected command left a file behind
+    fn run_in_bash(script: &str, vars: &[&str], cwd: &Path) -> (Vec<String>, bool) {
+        let mut full = script.to_string();
+        for var in vars {
+            full.push_str(&format!("\nprintf '%s\\n' \"${}\"", var));
+        }
+
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&full)
+            .current_dir(cwd)
+            .env("PATH", "/usr/bin:/bin")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let lines = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(String::from)
+            .collect();
+        let pwned = fs::read_dir(cwd).unwrap().next().is_some();
+        (lines, pwned)
+    }
+
+    #[test]
+    fn test_warning_statement() {
+        assert_eq!(
+            warning_statement("iron reached EOL on 2024-12-04"),
+            "printf '%s\\n' 'Warning: iron reached EOL on 2024-12-04' >&2\n"
+        );
+    }
+
+    #[test]
+    fn test_activation_script_quotes_hostile_paths() {
+        let prefix = hostile_prefix("activate");
+        let root = prefix.ancestors().nth(2).unwrap().to_path_buf();
+        let cwd = root.join("cwd");
+        fs::create_dir_all(&cwd).unwrap();
+
+        let base = EnvMap::from([("PATH".to_string(), "/usr/bin:/bin".to_string())]);
+        let script = render_activation_script("jazzy", &prefix, &base, &Config::default(), &[]);
+        let (lines, pwned) = run_in_bash(
+            &script,
+            &["AMENT_PREFIX_PATH", "PATH", "PYTHONPATH", "ROS_DISTRO"],
+            &cwd,
+        );
+        fs::remove_dir_all(&root).unwrap();
+
+        let prefix = prefix.display().to_string();
+        assert!(!pwned, "activation script executed injected commands");
+        assert_eq!(lines[0], prefix);
+        assert_eq!(lines[1], format!("/usr/bin:/bin:{}/bin", prefix));
+        assert_eq!(lines[2], format!("{}/lib/python3.12/site-packages", prefix));
+        assert_eq!(lines[3], "jazzy");
+    }
+
+    #[test]
+    fn test_activation_script_quotes_hostile_master_uri_and_hooks() {
+        let prefix = hostile_prefix("ros1");
+        fs::create_dir_all(prefix.join("share/ros")).unwrap();
+        let root = prefix.ancestors().nth(2).unwrap().to_path_buf();
+        let cwd = root.join("cwd");
+        fs::create_dir_all(&cwd).unwrap();
+
+        let hook = prefix.join("hook $(touch pwned-hook).sh");
+        fs::write(&hook, "export HOOK_RAN=yes\n").unwrap();
+
+        let uri = "http://robot:11311'; touch pwned-uri; echo '";
+        let mut config = Config::default();
+        config.ros1.master_uri = uri.to_string();
+        let script = render_activation_script("noetic", &prefix, &EnvMap::new(), &config, &[hook]);
+        let (lines, pwned) =
+            run_in_bash(&script, &["ROS_MASTER_URI", "ROS_ROOT", "HOOK_RAN"], &cwd);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(!pwned, "activation script executed injected commands");
+        assert_eq!(lines[0], uri);
+        assert_eq!(lines[1], format!("{}/share/ros", prefix.display()));
+        assert_eq!(lines[2], "yes");
+    }
+
+    // The shell side of activation before it moved into Rust: helper functions
+    // piping every path list through tr/grep/sed
+    const LEGACY_ACTIVATION: &str = r#"
+_rosenv_strip() {
+  printf '%s\n' "$1" | tr ':' '\n' | grep -v "/opt/ros/" | tr '\n' ':' | sed 's/:$//'
+}
+_rosenv_strip_var() {
+  local value
+  eval "value=\${$1}"
+  value="$(_rosenv_strip "$value")"
+  if [ -n "$value" ]; then export "$1=$value"; else unset "$1"; fi
+}
+_rosenv_append() {
+  local var_name="$1" dir="$2"
+  if [ -d "$dir" ]; then
+    local current
+    eval "current=\${$var_name}"
+    case ":${current}:" in
+      *":${dir}:"*) ;;
+      *) export "$var_name=${current:+${current}:}${dir}" ;;
+    esac
+  fi
+}
+export PATH="$(_rosenv_strip "$PATH")"
+export PYTHONPATH="$(_rosenv_strip "$PYTHONPATH")"
+export PKG_CONFIG_PATH="$(_rosenv_strip "$PKG_CONFIG_PATH")"
+export CMAKE_PREFIX_PATH="$(_rosenv_strip "$CMAKE_PREFIX_PATH")"
+export AMENT_PREFIX_PATH="$(_rosenv_strip "$AMENT_PREFIX_PATH")"
+unset GZ_VERSION
+_rosenv_strip_var GZ_SIM_RESOURCE_PATH
+_rosenv_strip_var GZ_SIM_SYSTEM_PLUGIN_PATH
+_rosenv_strip_var GZ_GUI_PLUGIN_PATH
+_rosenv_strip_var IGN_GAZEBO_RESOURCE_PATH
+_rosenv_strip_var IGN_GAZEBO_SYSTEM_PLUGIN_PATH
+_rosenv_strip_var IGN_GUI_PLUGIN_PATH
+unset ROS_ROOT ROS_ETC_DIR ROS_MASTER_URI
+_rosenv_strip_var ROS_PACKAGE_PATH
+export ROS_DISTRO=jazzy ROS_VERSION=2 ROS_PYTHON_VERSION=3
+_rosenv_append AMENT_PREFIX_PATH "$ROOT"
+_rosenv_append CMAKE_PREFIX_PATH "$ROOT"
+_rosenv_append PATH "$ROOT/bin"
+_rosenv_append PKG_CONFIG_PATH "$ROOT/lib/pkgconfig"
+for _rosenv_pypath in "$ROOT"/lib/python*/site-packages; do
+  _rosenv_append PYTHONPATH "$_rosenv_pypath"
+done
+unset -f _rosenv_strip _rosenv_strip_var _rosenv_append
+"#;
+
+    // Evaluates `script` `iterations` times in one bash process, so only the
+    // cost of evaluating the activation is measured, not starting the shell
+    fn time_in_bash(script: &str, root: &Path, iterations: u32) -> std::time::Duration {
+        let start = std::time::Instant::now();
+        let status = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(format!(
+                "for _ in $(seq {}); do\n\
+                 export PATH=/opt/ros/humble/bin:/usr/bin:/bin\n\
+                 export PYTHONPATH=/opt/ros/humble/lib/python3.10/site-packages\n\
+                 {}\ndone",
+                iterations, script
+            ))
+            .env("ROOT", root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        start.elapsed()
+    }
+
+    // Run with `cargo test -- --ignored --nocapture bench_activation`
+    #[test]
+    #[ignore]
+    fn bench_activation_shell_cost() {
+        let root = std::env::temp_dir().join(format!("rosenv-bench-{}", std::process::id()));
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::create_dir_all(root.join("lib/python3.12/site-packages")).unwrap();
+
+        let base = EnvMap::from([
+            (
+                "PATH".to_string(),
+                "/opt/ros/humble/bin:/usr/bin:/bin".to_string(),
+            ),
+            (
+                "PYTHONPATH".to_string(),
+                "/opt/ros/humble/lib/python3.10/site-packages".to_string(),
+            ),
+        ]);
+        let script = render_activation_script("jazzy", &root, &base, &Config::default(), &[]);
+
+        let iterations = 200;
+        let legacy = time_in_bash(LEGACY_ACTIVATION, &root, iterations);
+        let computed = time_in_bash(&script, &root, iterations);
+        fs::remove_dir_all(&root).unwrap();
+
+        println!(
+            "{} activations: shell pipelines {:?}, precomputed exports {:?}",
+            iterations, legacy, computed
+        );
+        assert!(computed < legacy);
+    }
+
     #[test]
     fn test_generate_shell_integration_zsh() {
         let script = generate_shell_integration("zsh");
@@ -174,6 +741,44 @@ mod tests {
 
         assert!(script.contains("rosenv init bash"));
         assert!(script.contains("rosenv()"));
+        assert!(script.contains("command rosenv activate --shell bash \"$2\""));
+        assert!(script.contains("command rosenv deactivate --shell bash)"));
+    }
+
+    #[test]
+    fn test_resolve_shell_prefers_flag() {
+        assert_eq!(resolve_shell(Some("zsh".to_string())), "zsh");
+        assert_eq!(resolve_shell(None), current_shell());
+    }
+
+    #[test]
+    fn test_shell_integration_installs_directory_hook() {
+        let script = generate_shell_integration("zsh");
+
+        assert!(script.contains("_rosenv_auto()"));
+        assert!(script.contains(".ros-distro"));
+        assert!(script.contains(".rosenv.toml"));
+        assert!(script.contains("add-zsh-hook chpwd _rosenv_auto"));
+        assert!(script.contains("PROMPT_COMMAND=\"_rosenv_auto"));
+    }
+
+    #[test]
+    fn test_generate_prompt_segment() {
+        let script = generate_prompt_segment();
+
+        assert!(script.contains("command rosenv prompt"));
+        assert!(script.contains("PROMPT_SUBST"));
+        assert!(script.contains("PS1="));
+    }
+
+    #[test]
+    fn test_generate_direnv_stdlib() {
+        let script = generate_direnv_stdlib();
+
+        assert!(script.contains("use_ros()"));
+        assert!(script.contains("--overlay"));
+        assert!(script.contains("watch_file"));
+        assert!(script.contains("rosenv env \"$distro\" --format shell"));
     }
 
     #[test]
@@ -198,9 +803,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deactivation_exports_emptied_path() {
+        let before = EnvMap::from([("PATH".to_string(), "/opt/ros/jazzy/bin".to_string())]);
+        let script = render_deactivation(&before, &Config::default());
+
+        assert!(script.starts_with("export PATH=''\n"));
+    }
+
     #[test]
     fn test_deactivation_script_unsets_all_vars() {
-        let script = generate_deactivation_script();
+        let script = generate_deactivation_script(None, "bash").unwrap();
 
         let required_unsets = vec![
             "ROS_DISTRO",
@@ -211,6 +824,13 @@ mod tests {
             "COLCON_PREFIX_PATH",
             "PYTHONPATH",
             "PKG_CONFIG_PATH",
+            "GZ_VERSION",
+            "GZ_SIM_RESOURCE_PATH",
+            "GZ_SIM_SYSTEM_PLUGIN_PATH",
+            "IGN_GAZEBO_RESOURCE_PATH",
+            "ROS_PACKAGE_PATH",
+            "ROS_ROOT",
+            "ROS_ETC_DIR",
         ];
 
         for var in required_unsets {
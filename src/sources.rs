@@ -0,0 +1,343 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{expand_home, DiscoveryConfig};
+use crate::distro::{
+    detect_env_distro, get_pixi_envs_dir, get_ros_root, list_distributions,
+    scan_pixi_ros_installations, validate_distro_name, Distribution, SourceKind,
+};
+use crate::overlay::is_colcon_install;
+
+const DPKG_INFO_DIR: &str = "/var/lib/dpkg/info";
+
+// Where conda, mamba and micromamba put named envs by default
+const CONDA_ENVS_DIRS: [&str; 6] = [
+    ".conda/envs",
+    "micromamba/envs",
+    "miniforge3/envs",
+    "mambaforge/envs",
+    "miniconda3/envs",
+    "anaconda3/envs",
+];
+
+// Every ROS deb, e.g. `ros-humble-rclcpp`, registers its file list with dpkg
+fn is_apt_install(distro: &str, dpkg_info: &Path) -> bool {
+    let prefix = format!("ros-{}-", distro);
+    fs::read_dir(dpkg_info)
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.starts_with(&prefix) && name.ends_with(".list")
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn is_under(path: &Path, dir: &Path) -> bool {
+    match (fs::canonicalize(path), fs::canonicalize(dir)) {
+        (Ok(path), Ok(dir)) => path.starts_with(dir),
+        _ => false,
+    }
+}
+
+pub fn classify_in(path: &Path, distro: &str, pixi_envs: &Path, dpkg_info: &Path) -> SourceKind {
+    if is_under(path, pixi_envs) {
+        SourceKind::PixiGlobal
+    } else if path.join("conda-meta").is_dir() {
+        SourceKind::CondaEnv
+    } else if is_apt_install(distro, dpkg_info) {
+        SourceKind::Apt
+    } else if is_colcon_install(path) {
+        SourceKind::SourceBuild
+    } else {
+        SourceKind::Manual
+    }
+}
+
+pub fn classify(path: &Path, distro: &str) -> SourceKind {
+    classify_in(path, distro, &get_pixi_envs_dir(), Path::new(DPKG_INFO_DIR))
+}
+
+// Everything that can be activated: the entries of /opt/ros, whatever their kind
+pub fn installed_distributions() -> Result<Vec<Distribution>> {
+    Ok(list_distributions()?
+        .into_iter()
+        .map(|name| {
+            let path = get_ros_root().join(&name);
+            let kind = classify(&path, &name);
+            Distribution {
+                name,
+                path,
+                kind,
+                declared: None,
+            }
+        })
+        .collect())
+}
+
+// conda keeps a list of every env it created, wherever it lives
+fn conda_environments_txt() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    fs::read_to_string(home.join(".conda/environments.txt"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn conda_env_candidates(config: &DiscoveryConfig) -> Vec<PathBuf> {
+    let mut envs_dirs: Vec<PathBuf> = Vec::new();
+    if let Some(root) = std::env::var_os("MAMBA_ROOT_PREFIX") {
+        envs_dirs.push(PathBuf::from(root).join("envs"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        envs_dirs.extend(CONDA_ENVS_DIRS.iter().map(|dir| home.join(dir)));
+    }
+    envs_dirs.extend(config.conda_envs_dirs.iter().map(|dir| expand_home(dir)));
+
+    let mut candidates = conda_environments_txt();
+    for envs_dir in envs_dirs {
+        if let Ok(entries) = fs::read_dir(&envs_dir) {
+            candidates.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+    candidates
+}
+
+pub fn discover_conda_envs(candidates: &[PathBuf], pixi_envs: &Path) -> Vec<Distribution> {
+    let mut distributions: Vec<Distribution> = Vec::new();
+    for path in candidates {
+        if !path.join("conda-meta").is_dir() || is_under(path, pixi_envs) {
+            continue;
+        }
+        if distributions.iter().any(|d| is_under(&d.path, path)) {
+            continue;
+        }
+
+        let env_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if let Some(name) = detect_env_distro(path, &env_name) {
+            distributions.push(Distribution {
+                name,
+                path: path.clone(),
+                kind: SourceKind::CondaEnv,
+                declared: None,
+            });
+        }
+    }
+    distributions
+}
+
+// ros_environment (ROS 2) and catkin (ROS 1) bake ROS_DISTRO into env hooks
+const DISTRO_HOOKS: [&str; 3] = [
+    "share/ros_environment/environment/1.ros_distro.sh",
+    "ros_environment/share/ros_environment/environment/1.ros_distro.sh",
+    "etc/catkin/profile.d/1.ros_distro.sh",
+];
+
+pub fn source_build_distro(install: &Path) -> Option<String> {
+    DISTRO_HOOKS.iter().find_map(|hook| {
+        let content = fs::read_to_string(install.join(hook)).ok()?;
+        content.lines().find_map(|line| {
+            let value = line
+                .trim()
+                .trim_start_matches("export ")
+                .strip_prefix("ROS_DISTRO=")?;
+            let value = value.trim_matches(|c| c == '"' || c == '\'');
+            validate_distro_name(value).ok().map(|_| value.to_string())
+        })
+    })
+}
+
+pub fn discover_source_builds(config: &DiscoveryConfig) -> Vec<Distribution> {
+    let mut distributions = Vec::new();
+    for install in &config.source_builds {
+        let install = expand_home(install);
+        match source_build_distro(&install) {
+            Some(name) if is_colcon_install(&install) => distributions.push(Distribution {
+                name,
+                path: install,
+                kind: SourceKind::SourceBuild,
+                declared: None,
+            }),
+            _ => eprintln!(
+                "Warning: {} is not a ROS source build (no ros_environment hook found)",
+                install.display()
+            ),
+        }
+    }
+    distributions
+}
+
+// Only one installation can own /opt/ros/<distro>. The first one wins, so
+// pixi envs take precedence over conda envs and those over source builds.
+pub fn dedup_distributions(
+    distributions: Vec<Distribution>,
+) -> (Vec<Distribution>, Vec<Distribution>) {
+    let mut kept: Vec<Distribution> = Vec::new();
+    let mut skipped = Vec::new();
+    for distribution in distributions {
+        if kept.iter().any(|d| d.name == distribution.name) {
+            skipped.push(distribution);
+        } else {
+            kept.push(distribution);
+        }
+    }
+    (kept, skipped)
+}
+
+// Installations that live outside /opt/ros and can be linked into it. apt and
+// manual installs are already there.
+pub fn discover_all(config: &DiscoveryConfig) -> Result<Vec<Distribution>> {
+    let mut distributions = scan_pixi_ros_installations()?;
+    distributions.sort_by(|a, b| a.path.cmp(&b.path));
+    distributions.extend(discover_conda_envs(
+        &conda_env_candidates(config),
+        &get_pixi_envs_dir(),
+    ));
+    distributions.extend(discover_source_builds(config));
+    // Stable, so installations of the same distro keep their precedence
+    distributions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (kept, skipped) = dedup_distributions(distributions);
+    for duplicate in &skipped {
+        if let Some(owner) = kept.iter().find(|d| d.name == duplicate.name) {
+            eprintln!(
+                "Warning: {} ({}) also provides {}; /opt/ros/{} links {} ({})",
+                duplicate.path.display(),
+                duplicate.kind.name(),
+                duplicate.name,
+                duplicate.name,
+                owner.path.display(),
+                owner.kind.name()
+            );
+        }
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("rosenv-sources-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_classify() {
+        let root = temp_root("classify");
+        let pixi_envs = root.join("pixi/envs");
+        let dpkg = root.join("dpkg");
+        for dir in [
+            "pixi/envs/ros-jazzy/conda-meta",
+            "micromamba/envs/robot/conda-meta",
+            "ws/install",
+            "opt/humble",
+            "opt/custom",
+            "dpkg",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("ws/install/.colcon_install_layout"), "merged\n").unwrap();
+        fs::write(dpkg.join("ros-humble-rclcpp.list"), "").unwrap();
+
+        let kinds: Vec<_> = [
+            ("pixi/envs/ros-jazzy", "jazzy"),
+            ("micromamba/envs/robot", "jazzy"),
+            ("ws/install", "rolling"),
+            ("opt/humble", "humble"),
+            ("opt/custom", "custom"),
+        ]
+        .iter()
+        .map(|(dir, distro)| classify_in(&root.join(dir), distro, &pixi_envs, &dpkg))
+        .collect();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            kinds,
+            vec![
+                SourceKind::PixiGlobal,
+                SourceKind::CondaEnv,
+                SourceKind::SourceBuild,
+                SourceKind::Apt,
+                SourceKind::Manual,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discover_conda_envs() {
+        let root = temp_root("conda");
+        let robot = root.join("envs/robot");
+        let tools = root.join("envs/tools");
+        let pixi = root.join("pixi/envs/ros-jazzy");
+        for env in [&robot, &tools, &pixi] {
+            fs::create_dir_all(env.join("conda-meta")).unwrap();
+        }
+        for env in [&robot, &pixi] {
+            fs::write(
+                env.join("conda-meta/ros2-distro-mutex-0.6.0-jazzy_7.json"),
+                "{}",
+            )
+            .unwrap();
+        }
+
+        // The same env listed twice must only show up once
+        let found = discover_conda_envs(
+            &[robot.clone(), tools, pixi, robot.clone()],
+            &root.join("pixi/envs"),
+        );
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "jazzy");
+        assert_eq!(found[0].path, robot);
+        assert_eq!(found[0].kind, SourceKind::CondaEnv);
+    }
+
+    #[test]
+    fn test_source_build_distro() {
+        let install = temp_root("source").join("install");
+        let hooks = install.join("share/ros_environment/environment");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(
+            hooks.join("1.ros_distro.sh"),
+            "# generated from ros_environment/env-hooks/1.ros_distro.sh.in\n\nexport ROS_DISTRO=rolling\n",
+        )
+        .unwrap();
+
+        let distro = source_build_distro(&install);
+        fs::remove_dir_all(install.parent().unwrap()).unwrap();
+
+        assert_eq!(distro.as_deref(), Some("rolling"));
+    }
+
+    #[test]
+    fn test_dedup_distributions() {
+        let distribution = |name: &str, path: &str, kind| Distribution {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            kind,
+            declared: None,
+        };
+        let (kept, skipped) = dedup_distributions(vec![
+            distribution("jazzy", "/pixi/envs/robot-stack", SourceKind::PixiGlobal),
+            distribution("jazzy", "/pixi/envs/sim", SourceKind::PixiGlobal),
+            distribution("jazzy", "/micromamba/envs/robot", SourceKind::CondaEnv),
+            distribution("rolling", "/ws/install", SourceKind::SourceBuild),
+        ]);
+
+        let kept: Vec<_> = kept.iter().map(|d| d.path.to_str().unwrap()).collect();
+        let skipped: Vec<_> = skipped.iter().map(|d| d.path.to_str().unwrap()).collect();
+        assert_eq!(kept, ["/pixi/envs/robot-stack", "/ws/install"]);
+        assert_eq!(skipped, ["/pixi/envs/sim", "/micromamba/envs/robot"]);
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::ErrorKind;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::load_config;
+use crate::distro::{detect_ros_version, validate_distro};
+use crate::env::{compute_activation, current_env, EnvMap};
+use crate::eol::enforce_eol;
+use crate::shell::current_shell;
+
+// Marks the environment of a `rosenv shell` child so nesting can be detected
+pub const SHELL_MARKER_VAR: &str = "ROSENV_SHELL";
+
+const BASH_RC: &str = r#"[ -f "$HOME/.bashrc" ] && . "$HOME/.bashrc"
+PS1="(ros:$ROSENV_SHELL) $PS1"
+"#;
+
+const ZSH_ENV: &str = r#"[ -f "${ROSENV_ZDOTDIR:-$HOME}/.zshenv" ] && . "${ROSENV_ZDOTDIR:-$HOME}/.zshenv"
+"#;
+
+const ZSH_RC: &str = r#"ZDOTDIR="${ROSENV_ZDOTDIR:-$HOME}"
+unset ROSENV_ZDOTDIR
+[ -f "$ZDOTDIR/.zshrc" ] && . "$ZDOTDIR/.zshrc"
+PROMPT="(ros:$ROSENV_SHELL) $PROMPT"
+"#;
+
+const FISH_INIT: &str = r#"functions -c fish_prompt _rosenv_fish_prompt
+function fish_prompt
+    echo -n "(ros:$ROSENV_SHELL) "
+    _rosenv_fish_prompt
+end"#;
+
+// Builds the command for the user's shell so that it loads its usual rc files
+// and then prefixes the prompt with the active distro
+fn shell_command(program: &str, shell: &str, rc_dir: &Path, env: &mut EnvMap) -> Result<Command> {
+    let mut command = Command::new(program);
+
+    match shell {
+        "bash" => {
+            let rc = rc_dir.join("bashrc");
+            fs::write(&rc, BASH_RC)?;
+            command.arg("--rcfile").arg(rc).arg("-i");
+        }
+        "zsh" => {
+            fs::write(rc_dir.join(".zshenv"), ZSH_ENV)?;
+            fs::write(rc_dir.join(".zshrc"), ZSH_RC)?;
+            if let Some(zdotdir) = env.get("ZDOTDIR").cloned() {
+                env.insert("ROSENV_ZDOTDIR".to_string(), zdotdir);
+            }
+            env.insert("ZDOTDIR".to_string(), rc_dir.display().to_string());
+            command.arg("-i");
+        }
+        "fish" => {
+            command.arg("-C").arg(FISH_INIT);
+        }
+        _ => {
+            let ps1 = env.get("PS1").cloned().unwrap_or_else(|| "$ ".to_string());
+            let distro = env.get(SHELL_MARKER_VAR).cloned().unwrap_or_default();
+            env.insert("PS1".to_string(), format!("(ros:{}) {}", distro, ps1));
+        }
+    }
+
+    Ok(command)
+}
+
+// A fresh private directory for the rc files. Creation fails if the path
+// already exists, so another user cannot plant it or a symlink in advance.
+fn create_rc_dir() -> Result<PathBuf> {
+    for _ in 0..16 {
+        // RandomState is seeded from the OS for every instance
+        let suffix = RandomState::new().build_hasher().finish();
+        let dir = std::env::temp_dir().join(format!(
+            "rosenv-shell-{}-{:016x}",
+            std::process::id(),
+            suffix
+        ));
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).context("Failed to create temporary shell config"),
+        }
+    }
+    anyhow::bail!("Failed to create temporary shell config: no unused directory name")
+}
+
+pub fn cmd_shell(distro: &str) -> Result<()> {
+    let distro_path = validate_distro(distro)?;
+
+    if let Ok(outer) = std::env::var(SHELL_MARKER_VAR) {
+        eprintln!(
+            "⚠ Already inside a rosenv shell for {}; starting a nested shell for {}",
+            outer, distro
+        );
+        eprintln!("  Type 'exit' to return to the {} shell\n", outer);
+    }
+
+    let program = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+    let shell = current_shell();
+
+    let config = load_config()?;
+    enforce_eol(distro, &config.eol)?;
+    let mut env = compute_activation(distro, &distro_path, &current_env(), &config);
+    env.insert(SHELL_MARKER_VAR.to_string(), distro.to_string());
+
+    let ros_version = detect_ros_version(&distro_path, distro);
+    let rc_dir = create_rc_dir()?;
+
+    let status = shell_command(&program, &shell, &rc_dir, &mut env).and_then(|mut command| {
+        println!(
+            "Entering ROS {} {} shell. Type 'exit' to leave.",
+            ros_version, distro
+        );
+        command
+            .env_clear()
+            .envs(&env)
+            .status()
+            .context(format!("Failed to start {}", program))
+    });
+
+    let _ = fs::remove_dir_all(&rc_dir);
+
+    let status = status?;
+    println!("Left ROS {} {} shell", ros_version, distro);
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rc_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rosenv-subshell-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_create_rc_dir_is_private_and_unique() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = create_rc_dir().unwrap();
+        let second = create_rc_dir().unwrap();
+        let mode = fs::metadata(&first).unwrap().permissions().mode();
+        fs::remove_dir_all(&first).unwrap();
+        fs::remove_dir_all(&second).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn test_bash_uses_rcfile() {
+        let dir = rc_dir("bash");
+        let mut env = EnvMap::new();
+        let command = shell_command("/bin/bash", "bash", &dir, &mut env).unwrap();
+        let rc = fs::read_to_string(dir.join("bashrc")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args[0], "--rcfile");
+        assert!(rc.contains(". \"$HOME/.bashrc\""));
+        assert!(rc.contains("(ros:$ROSENV_SHELL)"));
+    }
+
+    #[test]
+    fn test_zsh_redirects_zdotdir() {
+        let dir = rc_dir("zsh");
+        let mut env = EnvMap::new();
+        env.insert("ZDOTDIR".to_string(), "/home/user/.zsh".to_string());
+        shell_command("/bin/zsh", "zsh", &dir, &mut env).unwrap();
+        let has_rc = dir.join(".zshrc").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(has_rc);
+        assert_eq!(env["ZDOTDIR"], dir.display().to_string());
+        assert_eq!(env["ROSENV_ZDOTDIR"], "/home/user/.zsh");
+    }
+
+    #[test]
+    fn test_other_shells_get_ps1() {
+        let dir = rc_dir("sh");
+        let mut env = EnvMap::new();
+        env.insert(SHELL_MARKER_VAR.to_string(), "humble".to_string());
+        shell_command("/bin/sh", "sh", &dir, &mut env).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(env["PS1"], "(ros:humble) $ ");
+    }
+}
@@ -4,7 +4,9 @@ use std::io::{self, Write};
 use std::os::unix::fs as unix_fs;
 use std::path::Path;
 
-use crate::distro::{get_ros_root, list_distributions, scan_pixi_ros_installations};
+use crate::config::load_config;
+use crate::distro::{distro_link_path, get_ros_root, list_distributions, SourceKind};
+use crate::sources::{classify, discover_all};
 
 pub fn check_opt_ros_writable() -> Result<()> {
     let ros_root = get_ros_root();
@@ -30,7 +32,27 @@ pub fn check_opt_ros_writable() -> Result<()> {
 }
 
 pub fn create_symlink(distro: &str, target: &Path, force: bool) -> Result<()> {
-    let link_path = get_ros_root().join(distro);
+    let link_path = distro_link_path(distro)?;
+
+    // Real directories are system installs; only ever replace symlinks
+    if link_path.exists() && !link_path.is_symlink() {
+        if classify(&link_path, distro) == SourceKind::Apt {
+            println!(
+                "  ⚠ /opt/ros/{} is managed by apt, not linking {}",
+                distro,
+                target.display()
+            );
+            println!("    Remove it with: sudo apt remove 'ros-{}-*'", distro);
+        } else {
+            println!(
+                "  ⚠ /opt/ros/{} is a directory, not linking {}",
+                distro,
+                target.display()
+            );
+            println!("    Remove or rename it by hand, then run: rosenv setup");
+        }
+        return Ok(());
+    }
 
     if link_path.exists() {
         if !force {
@@ -62,11 +84,7 @@ pub fn create_symlink(distro: &str, target: &Path, force: bool) -> Result<()> {
             }
         }
 
-        if link_path.is_symlink() {
-            fs::remove_file(&link_path)?;
-        } else if link_path.is_dir() {
-            fs::remove_dir_all(&link_path)?;
-        }
+        fs::remove_file(&link_path)?;
     }
 
     unix_fs::symlink(target, &link_path)
@@ -76,13 +94,36 @@ pub fn create_symlink(distro: &str, target: &Path, force: bool) -> Result<()> {
     Ok(())
 }
 
+// Like cleanup, remove only ever deletes symlinks; real directories are
+// installations and are left to their own tools
+fn check_removable(link_path: &Path, distro: &str) -> Result<()> {
+    if link_path.is_symlink() {
+        return Ok(());
+    }
+
+    match classify(link_path, distro) {
+        SourceKind::Apt => anyhow::bail!(
+            "{} is managed by apt\n\nRemove it with: sudo apt remove 'ros-{}-*'",
+            link_path.display(),
+            distro
+        ),
+        kind => anyhow::bail!(
+            "{} is a {} installation, not a symlink\n\nrosenv only removes symlinks; remove or rename it by hand",
+            link_path.display(),
+            kind.name()
+        ),
+    }
+}
+
 pub fn remove_symlink(distro: &str) -> Result<()> {
-    let link_path = get_ros_root().join(distro);
+    let link_path = distro_link_path(distro)?;
 
-    if !link_path.exists() {
+    if !link_path.exists() && !link_path.is_symlink() {
         anyhow::bail!("Distribution '{}' not found in /opt/ros", distro);
     }
 
+    check_removable(&link_path, distro)?;
+
     print!("Remove /opt/ros/{}? [y/N] ", distro);
     io::stdout().flush()?;
 
@@ -94,18 +135,14 @@ pub fn remove_symlink(distro: &str) -> Result<()> {
         return Ok(());
     }
 
-    if link_path.is_symlink() {
-        fs::remove_file(&link_path)?;
-    } else if link_path.is_dir() {
-        fs::remove_dir_all(&link_path)?;
-    }
+    fs::remove_file(&link_path)?;
 
     println!("✓ Removed /opt/ros/{}", distro);
 
-    if let Ok(distros) = scan_pixi_ros_installations() {
+    if let Ok(distros) = load_config().and_then(|config| discover_all(&config.discovery)) {
         for d in distros {
             if d.name == distro {
-                println!("\nNote: The pixi installation remains at:");
+                println!("\nNote: The {} installation remains at:", d.kind.name());
                 println!("  {}", d.path.display());
                 println!("\nTo reinstall the symlink: rosenv setup");
                 break;
@@ -117,7 +154,11 @@ pub fn remove_symlink(distro: &str) -> Result<()> {
 }
 
 pub fn cmd_cleanup() -> Result<()> {
-    let distros = list_distributions()?;
+    // Real directories (apt, manual installs) are never touched here
+    let distros: Vec<String> = list_distributions()?
+        .into_iter()
+        .filter(|distro| get_ros_root().join(distro).is_symlink())
+        .collect();
 
     if distros.is_empty() {
         println!("No symlinks found in /opt/ros");
@@ -150,7 +191,7 @@ pub fn cmd_cleanup() -> Result<()> {
     }
 
     println!("\nCleanup complete.");
-    println!("\nNote: Pixi installations remain in ~/.pixi/envs/");
+    println!("\nNote: the installations the symlinks pointed to remain in place");
     println!("To recreate symlinks: rosenv setup");
 
     Ok(())
@@ -160,7 +201,7 @@ pub fn cmd_refresh() -> Result<()> {
     println!("Scanning for changes...\n");
 
     let existing = list_distributions()?;
-    let pixi_distros = scan_pixi_ros_installations()?;
+    let pixi_distros = discover_all(&load_config()?.discovery)?;
 
     if !existing.is_empty() {
         println!("Existing symlinks:");
@@ -189,7 +230,12 @@ pub fn cmd_refresh() -> Result<()> {
     if !new_distros.is_empty() {
         println!("New distributions found:");
         for distro in &new_distros {
-            println!("  + {} → {}", distro.name, distro.path.display());
+            println!(
+                "  + {} ({}) → {}",
+                distro.name,
+                distro.kind.name(),
+                distro.path.display()
+            );
         }
         println!();
 
@@ -207,3 +253,25 @@ pub fn cmd_refresh() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_removable_refuses_real_directories() {
+        let root = std::env::temp_dir().join(format!("rosenv-symlink-{}", std::process::id()));
+        let install = root.join("ws/install");
+        fs::create_dir_all(&install).unwrap();
+        fs::write(install.join(".colcon_install_layout"), "merged\n").unwrap();
+        let link = root.join("rosenv-test-src");
+        unix_fs::symlink(&install, &link).unwrap();
+
+        let err = check_removable(&install, "rosenv-test-src").unwrap_err();
+        let linked = check_removable(&link, "rosenv-test-src");
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(err.to_string().contains("source-build installation"));
+        assert!(linked.is_ok());
+    }
+}